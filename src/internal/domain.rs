@@ -1,4 +1,10 @@
+pub mod dedup;
 pub mod event;
+pub mod extractor;
 pub mod filter_key;
+pub mod level;
+pub mod parser;
+pub mod patterns;
 pub mod record;
 pub mod records;
+pub mod redact;
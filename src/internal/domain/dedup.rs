@@ -0,0 +1,135 @@
+use std::borrow::Cow;
+use std::sync::LazyLock;
+use std::time::SystemTime;
+
+use regex::Regex;
+
+use super::record::{Record, TimeSource};
+use crate::internal::common::strip_ansi;
+
+static NUMBERS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+").unwrap());
+
+/// Which consecutive records are collapsed into one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dedup {
+    #[default]
+    Off,
+    /// Records with the same text
+    Exact,
+    /// Records with the same text once numbers (and so timestamps) are masked
+    Masked,
+}
+
+impl Dedup {
+    pub fn next(self) -> Self {
+        match self {
+            Dedup::Off => Dedup::Exact,
+            Dedup::Exact => Dedup::Masked,
+            Dedup::Masked => Dedup::Off,
+        }
+    }
+
+    fn key<'a>(&self, record: &'a Record) -> Cow<'a, str> {
+        match self {
+            Dedup::Off | Dedup::Exact => Cow::Borrowed(record.as_ref()),
+            Dedup::Masked => Cow::Owned(
+                NUMBERS
+                    .replace_all(&strip_ansi(record.as_ref()), "#")
+                    .into_owned(),
+            ),
+        }
+    }
+
+    /// Splits indexed `records` into runs of duplicates, every record is its own run when off.
+    pub fn groups<'a>(&self, records: impl Iterator<Item = (usize, &'a Record)>) -> Vec<Group<'a>> {
+        if *self == Dedup::Off {
+            return records
+                .map(|record| Group {
+                    records: vec![record],
+                })
+                .collect();
+        }
+        let mut groups: Vec<Group> = vec![];
+        let mut last_key = None;
+        for record in records {
+            let key = self.key(record.1);
+            match groups.last_mut() {
+                Some(group) if last_key.as_ref() == Some(&key) => {
+                    group.records.push(record);
+                }
+                _ => groups.push(Group {
+                    records: vec![record],
+                }),
+            }
+            last_key = Some(key);
+        }
+        groups
+    }
+}
+
+/// Consecutive duplicate records with their indexes.
+pub struct Group<'a> {
+    pub records: Vec<(usize, &'a Record)>,
+}
+
+impl Group<'_> {
+    /// Time the first and the last duplicate were written (or arrived) at.
+    pub fn seen(&self) -> (SystemTime, SystemTime) {
+        let time = |record: &Record| record.time(TimeSource::Parsed);
+        (
+            time(self.records[0].1),
+            time(self.records[self.records.len() - 1].1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(dedup: Dedup, lines: &[&str]) -> Vec<usize> {
+        let records: Vec<Record> = lines.iter().map(|line| Record::from(*line)).collect();
+        dedup
+            .groups(records.iter().enumerate())
+            .iter()
+            .map(|group| group.records.len())
+            .collect()
+    }
+
+    #[test]
+    fn collapses_consecutive_duplicates() {
+        let lines = [
+            "cache miss",
+            "cache miss",
+            "cache miss",
+            "retry 1 of 3",
+            "retry 2 of 3",
+            "cache miss",
+        ];
+        assert_eq!(counts(Dedup::Off, &lines), [1, 1, 1, 1, 1, 1]);
+        assert_eq!(counts(Dedup::Exact, &lines), [3, 1, 1, 1]);
+        assert_eq!(counts(Dedup::Masked, &lines), [3, 2, 1]);
+    }
+
+    #[test]
+    fn masked_ignores_colors_and_timestamps() {
+        let lines = [
+            "2024-05-01T12:00:01Z \x1b[33mWARN\x1b[0m slow query",
+            "2024-05-01T12:00:02Z WARN slow query",
+            "2024-05-01T12:00:03Z WARN slow queries",
+        ];
+        assert_eq!(counts(Dedup::Exact, &lines), [1, 1, 1]);
+        assert_eq!(counts(Dedup::Masked, &lines), [2, 1]);
+    }
+
+    #[test]
+    fn groups_keep_record_indexes() {
+        let records: Vec<Record> = ["a", "b", "b"].into_iter().map(Record::from).collect();
+        let groups = Dedup::Exact.groups(records.iter().enumerate());
+        let indexes: Vec<Vec<usize>> = groups
+            .iter()
+            .map(|group| group.records.iter().map(|(i, _)| *i).collect())
+            .collect();
+        assert_eq!(indexes, [vec![0], vec![1, 2]]);
+    }
+}
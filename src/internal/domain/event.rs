@@ -4,4 +4,15 @@ use super::record::Record;
 pub enum Event {
     StdIn(Record),
     KeyInput(crossterm::event::KeyEvent),
+    Mouse(crossterm::event::MouseEvent),
+    /// Text pasted into the terminal, with bracketed paste on.
+    Paste(String),
+    /// The terminal got `width` columns and `height` rows.
+    Resize(u16, u16),
+    /// The terminal window gained (`true`) or lost focus.
+    Focus(bool),
+    /// Drop every record received so far (e.g. replay seeking backwards).
+    Clear,
+    /// Nothing new arrived, but something on screen (e.g. a clock) changed.
+    Tick,
 }
@@ -0,0 +1,36 @@
+use std::borrow::Cow;
+
+use regex::Regex;
+
+use super::record::Record;
+
+/// Takes a value out of a record: a parsed field or a regex capture.
+#[derive(Debug, Clone)]
+pub enum Extractor {
+    Field(String),
+    /// The first capture group (or the whole match) of `regex` in the record text.
+    Capture {
+        name: String,
+        regex: Regex,
+    },
+}
+
+impl Extractor {
+    pub fn name(&self) -> &str {
+        match self {
+            Extractor::Field(name) => name,
+            Extractor::Capture { name, .. } => name,
+        }
+    }
+
+    pub fn extract<'a>(&self, record: &'a Record) -> Option<Cow<'a, str>> {
+        match self {
+            Extractor::Field(name) => record.field(name).map(Cow::Borrowed),
+            Extractor::Capture { regex, .. } => {
+                let captures = regex.captures(record.as_ref())?;
+                let value = captures.get(1).or_else(|| captures.get(0))?;
+                Some(Cow::Borrowed(value.as_str()))
+            }
+        }
+    }
+}
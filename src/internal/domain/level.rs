@@ -0,0 +1,171 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Severity of a record, from the least to the most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+/// How many words from the start of a plain line are searched for a level.
+const SEARCH_WORDS: usize = 8;
+
+impl Level {
+    pub const ALL: [Level; 6] = [
+        Level::Trace,
+        Level::Debug,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+        Level::Fatal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+            Level::Fatal => "FATAL",
+        }
+    }
+
+    /// Next minimum level to filter by, `None` shows everything.
+    pub fn next_threshold(threshold: Option<Level>) -> Option<Level> {
+        match threshold {
+            None => Some(Level::Debug),
+            Some(Level::Trace) | Some(Level::Debug) => Some(Level::Info),
+            Some(Level::Info) => Some(Level::Warn),
+            Some(Level::Warn) => Some(Level::Error),
+            Some(Level::Error) => Some(Level::Fatal),
+            Some(Level::Fatal) => None,
+        }
+    }
+
+    /// Parses a level name (`warn`, `WARNING`, `crit`, …).
+    pub fn from_name(name: &str) -> Option<Level> {
+        match name.to_lowercase().as_str() {
+            "trace" | "trc" => Some(Level::Trace),
+            "debug" | "dbg" => Some(Level::Debug),
+            "info" | "inf" | "information" | "notice" => Some(Level::Info),
+            "warn" | "wrn" | "warning" => Some(Level::Warn),
+            "error" | "err" | "eror" => Some(Level::Error),
+            "fatal" | "ftl" | "critical" | "crit" | "panic" | "alert" | "emerg" | "emergency" => {
+                Some(Level::Fatal)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a syslog severity (0 emergency … 7 debug).
+    pub fn from_syslog(severity: u8) -> Option<Level> {
+        match severity {
+            0..=2 => Some(Level::Fatal),
+            3 => Some(Level::Error),
+            4 => Some(Level::Warn),
+            5 | 6 => Some(Level::Info),
+            7 => Some(Level::Debug),
+            _ => None,
+        }
+    }
+
+    /// Parses the value of a structured `level` field: a name, a syslog severity
+    /// or a bunyan/pino number (10 trace … 60 fatal).
+    pub fn from_field(value: &str) -> Option<Level> {
+        match value.trim().parse::<u16>() {
+            Ok(severity @ 0..=7) => Level::from_syslog(severity as u8),
+            Ok(10) => Some(Level::Trace),
+            Ok(20) => Some(Level::Debug),
+            Ok(30) => Some(Level::Info),
+            Ok(40) => Some(Level::Warn),
+            Ok(50) => Some(Level::Error),
+            Ok(60) => Some(Level::Fatal),
+            Ok(_) => None,
+            Err(_) => Level::from_name(value.trim()),
+        }
+    }
+
+    /// Finds the level of a plain line and the char range of its token.
+    ///
+    /// Either a syslog `<PRI>` prefix, or one of the first words being a level
+    /// in upper case (`WARN`) or in brackets (`[warn]`).
+    pub fn find(text: &str) -> Option<(Level, Range<usize>)> {
+        if let Some(rest) = text.strip_prefix('<')
+            && let Some((priority, _)) = rest.split_once('>')
+            && (1..=3).contains(&priority.len())
+            && let Ok(value) = priority.parse::<u8>()
+        {
+            return Level::from_syslog(value % 8).map(|l| (l, 0..priority.len() + 2));
+        }
+
+        let mut start = 0;
+        for word in text.split(' ').take(SEARCH_WORDS) {
+            let len = word.chars().count();
+            let core = word.trim_start_matches(['[', '(', '<', '|']);
+            let offset = len - core.chars().count();
+            let bracketed = offset > 0;
+            let core = core.trim_end_matches([']', ')', '>', '|', ':', ',']);
+
+            if (bracketed || !core.chars().any(char::is_lowercase))
+                && let Some(level) = Level::from_name(core)
+            {
+                let begin = start + offset;
+                return Some((level, begin..begin + core.chars().count()));
+            }
+            start += len + 1;
+        }
+        None
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_syslog_priorities() {
+        // facility 1 (user) * 8 + severity
+        assert_eq!(
+            Level::find("<11>Oct 11 22:14:15 app: down"),
+            Some((Level::Error, 0..4))
+        );
+        assert_eq!(Level::find("<12>app: slow"), Some((Level::Warn, 0..4)));
+        assert_eq!(Level::find("<0>kernel panic"), Some((Level::Fatal, 0..3)));
+        assert_eq!(
+            Level::find("<15>app: details").map(|(l, _)| l),
+            Some(Level::Debug)
+        );
+        assert_eq!(Level::find("<1234>not a priority"), None);
+    }
+
+    #[test]
+    fn finds_level_words() {
+        assert_eq!(
+            Level::find("2024-05-01 12:00:00 WARN disk almost full"),
+            Some((Level::Warn, 20..24))
+        );
+        assert_eq!(Level::find("[error] failed"), Some((Level::Error, 1..6)));
+        // lower case words are part of the message
+        assert_eq!(Level::find("no error here"), None);
+    }
+
+    #[test]
+    fn parses_level_fields() {
+        assert_eq!(Level::from_field("warning"), Some(Level::Warn));
+        assert_eq!(Level::from_field("3"), Some(Level::Error));
+        assert_eq!(Level::from_field("50"), Some(Level::Error));
+        assert_eq!(Level::from_field("verbose"), None);
+    }
+}
@@ -0,0 +1,269 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, NaiveDateTime};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use super::level::Level;
+use super::record::{Format, LEVEL_KEYS, Record, TIME_KEYS};
+use crate::internal::common::strip_ansi;
+
+/// User-defined parser, named captures of the pattern become fields.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    name: String,
+    regex: Regex,
+    sources: Vec<String>,
+}
+
+impl Rule {
+    pub fn new(name: String, regex: Regex, sources: Vec<String>) -> Self {
+        Self {
+            name,
+            regex,
+            sources,
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    fn applies_to(&self, source: &str) -> bool {
+        self.sources.is_empty() || self.sources.iter().any(|s| s == source)
+    }
+    fn parse(&self, text: &str) -> Option<Vec<(String, String)>> {
+        let captures = self.regex.captures(text)?;
+        let fields: Vec<_> = self
+            .regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| {
+                let value = captures.name(name)?;
+                Some((name.to_owned(), value.as_str().to_owned()))
+            })
+            .collect();
+        (!fields.is_empty()).then_some(fields)
+    }
+}
+
+/// Detects the structure and the level of a record and splits it into fields.
+///
+/// User rules for the record source go first, then JSON and logfmt.
+pub fn parse(record: &mut Record, rules: &[Rule]) {
+    let plain = strip_ansi(record.as_ref());
+    let custom = rules
+        .iter()
+        .filter(|rule| rule.applies_to(record.source()))
+        .find_map(|rule| rule.parse(plain.trim_end()));
+
+    if let Some(fields) = custom {
+        record.set_fields(Format::Custom, fields);
+    } else if let Some(fields) = parse_json(record.as_ref()) {
+        record.set_fields(Format::Json, fields);
+    } else if let Some(fields) = parse_logfmt(record.as_ref()) {
+        record.set_fields(Format::Logfmt, fields);
+    }
+
+    let level = match record.find_field(&LEVEL_KEYS) {
+        Some((_, value)) => Level::from_field(value),
+        None => Level::find(&plain).map(|(level, _)| level),
+    };
+    record.set_level(level);
+
+    let timestamp = match record.find_field(&TIME_KEYS) {
+        Some((_, value)) => parse_time(value),
+        // plain lines usually start with the time
+        None => parse_leading_time(&plain),
+    };
+    record.set_timestamp(timestamp);
+}
+
+/// Finds the time a plain line starts with, as one word (`2024-05-01T12:00:00Z`,
+/// `[1714564800]`) or two (`2024-05-01 12:00:00`).
+///
+/// Bare numbers are only taken as Unix times between the years 2000 and 2100,
+/// so status codes and PIDs (`200 OK`, `1234 worker started`) aren't.
+fn parse_leading_time(plain: &str) -> Option<SystemTime> {
+    let mut words = plain
+        .split_whitespace()
+        .map(|word| word.trim_matches(['[', ']']));
+    let first = words.next()?;
+    if let Some(second) = words.next()
+        && let Ok(time) =
+            NaiveDateTime::parse_from_str(&format!("{} {}", first, second), "%Y-%m-%d %H:%M:%S%.f")
+    {
+        return Some(time.and_utc().into());
+    }
+    let time = parse_time(first)?;
+    if first.parse::<f64>().is_ok() {
+        // 2000-01-01 and 2100-01-01
+        let plausible = Duration::from_secs(946_684_800)..Duration::from_secs(4_102_444_800);
+        let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+        return plausible.contains(&since_epoch).then_some(time);
+    }
+    Some(time)
+}
+
+/// Parses RFC 3339 (`2024-05-01T12:00:00.123Z`), `2024-05-01 12:00:00`
+/// (taken as UTC) or a Unix time in seconds, milliseconds or microseconds.
+pub fn parse_time(value: &str) -> Option<SystemTime> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.into());
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(time.and_utc().into());
+        }
+    }
+    let number: f64 = value.parse().ok()?;
+    // 1e11 seconds is year 5138, so bigger numbers are finer units
+    let secs = match number {
+        n if n < 0.0 => return None,
+        n if n < 1e11 => n,
+        n if n < 1e14 => n / 1e3,
+        n if n < 1e17 => n / 1e6,
+        _ => return None,
+    };
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs_f64(secs))
+}
+
+/// Parses a JSON object, nested keys are flattened with dots (`http.status`).
+pub fn parse_json(text: &str) -> Option<Vec<(String, String)>> {
+    let text = text.trim();
+    if !(text.starts_with('{') && text.ends_with('}')) {
+        return None;
+    }
+    let Ok(Value::Object(map)) = serde_json::from_str(text) else {
+        return None;
+    };
+    let mut fields = vec![];
+    flatten("", &map, &mut fields);
+    Some(fields)
+}
+
+fn flatten(prefix: &str, map: &Map<String, Value>, fields: &mut Vec<(String, String)>) {
+    for (key, value) in map {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Object(map) => flatten(&key, map, fields),
+            Value::String(value) => fields.push((key, value.clone())),
+            value => fields.push((key, value.to_string())),
+        }
+    }
+}
+
+/// Parses a logfmt line (`level=info msg="started" dur=3ms`).
+///
+/// Every token must be a `key=value` pair and there must be at least two of them,
+/// so plain text which merely contains a `=` isn't mistaken for logfmt.
+pub fn parse_logfmt(text: &str) -> Option<Vec<(String, String)>> {
+    let mut fields = vec![];
+    let mut chars = text.trim().chars().peekable();
+
+    while chars.peek().is_some() {
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| *c != '=' && !c.is_whitespace()) {
+            key.push(c);
+        }
+        if !is_logfmt_key(&key) || chars.next() != Some('=') {
+            return None;
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => match chars.next()? {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        c => value.push(c),
+                    },
+                    c => value.push(c),
+                }
+            }
+            if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                return None;
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                value.push(c);
+            }
+        }
+        fields.push((key, value));
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    (fields.len() >= 2).then_some(fields)
+}
+
+fn is_logfmt_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || "_.-/".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leading_time_takes_date_and_time_words() {
+        let time = parse_leading_time("2024-05-01 12:00:00 started").unwrap();
+        assert_eq!(time, parse_time("2024-05-01T12:00:00Z").unwrap());
+        assert!(parse_leading_time("[2024-05-01T12:00:00Z] started").is_some());
+        assert!(parse_leading_time("1714564800 started").is_some());
+        assert!(parse_leading_time("1714564800123 started").is_some());
+    }
+
+    fn parsed(text: &str) -> Record {
+        let mut record = Record::new(text.to_owned());
+        parse(&mut record, &[]);
+        record
+    }
+
+    #[test]
+    fn takes_the_level_of_json_records() {
+        let record = parsed(r#"{"level":"warn","msg":"slow"}"#);
+        assert_eq!(record.format(), Format::Json);
+        assert_eq!(record.level(), Some(Level::Warn));
+        // pino levels are numbers
+        assert_eq!(
+            parsed(r#"{"level":50,"msg":"failed"}"#).level(),
+            Some(Level::Error)
+        );
+        assert_eq!(
+            parsed(r#"{"severity":"ERROR","http":{"status":500}}"#).field("http.status"),
+            Some("500")
+        );
+    }
+
+    #[test]
+    fn parses_logfmt() {
+        assert_eq!(
+            parse_logfmt(r#"level=info msg="user logged in" dur=3ms empty="#),
+            Some(vec![
+                ("level".to_owned(), "info".to_owned()),
+                ("msg".to_owned(), "user logged in".to_owned()),
+                ("dur".to_owned(), "3ms".to_owned()),
+                ("empty".to_owned(), "".to_owned()),
+            ])
+        );
+        assert_eq!(parse_logfmt("total=3 of them"), None);
+        assert_eq!(parse_logfmt("a=1"), None);
+        assert_eq!(parsed("level=error msg=failed").level(), Some(Level::Error));
+    }
+
+    #[test]
+    fn leading_time_skips_numbers_which_are_not_times() {
+        assert_eq!(parse_leading_time("200 OK"), None);
+        assert_eq!(parse_leading_time("1234 worker started"), None);
+        assert_eq!(parse_leading_time("started"), None);
+    }
+}
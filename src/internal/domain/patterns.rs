@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use super::record::{MESSAGE_KEYS, Record};
+use crate::internal::common::strip_ansi;
+
+pub const WILDCARD: &str = "<*>";
+/// Share of equal words for a message to join a pattern.
+const SIMILARITY: f64 = 0.4;
+
+/// Template of similar messages, variable tokens replaced with `<*>`.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    tokens: Vec<String>,
+    count: usize,
+    first: SystemTime,
+    last: SystemTime,
+    sample: String,
+}
+
+impl Pattern {
+    pub fn template(&self) -> String {
+        self.tokens.join(" ")
+    }
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    /// Time of the first and the last member.
+    pub fn seen(&self) -> (SystemTime, SystemTime) {
+        (self.first, self.last)
+    }
+    /// The first member.
+    pub fn sample(&self) -> &str {
+        &self.sample
+    }
+
+    /// Share of equal words (wildcards don't count) and the number of wildcards.
+    fn similarity(&self, tokens: &[String]) -> (f64, usize) {
+        let mut equal = 0;
+        let mut wildcards = 0;
+        for (a, b) in self.tokens.iter().zip(tokens) {
+            if a == WILDCARD {
+                wildcards += 1;
+            } else if a == b {
+                equal += 1;
+            }
+        }
+        (equal as f64 / tokens.len().max(1) as f64, wildcards)
+    }
+}
+
+/// Groups messages into patterns as they come, a simplified Drain.
+///
+/// Messages are split into words, words with digits are variable from the start.
+/// Candidates share the word count and the first word, the most similar one
+/// (with the most wildcards on a tie) is joined when enough words are equal,
+/// differing words become `<*>`.
+#[derive(Debug, Clone, Default)]
+pub struct Patterns {
+    patterns: Vec<Pattern>,
+    /// Pattern ids by word count and first word.
+    groups: HashMap<(usize, String), Vec<usize>>,
+}
+
+impl Patterns {
+    /// Adds a record and returns the id of its pattern.
+    pub fn add(&mut self, record: &Record) -> usize {
+        let text = match record.find_field(&MESSAGE_KEYS) {
+            Some((_, message)) => message.to_owned(),
+            None => strip_ansi(record.as_ref()),
+        };
+        let tokens: Vec<String> = text
+            .split_whitespace()
+            .map(|token| {
+                if token.chars().any(|c| c.is_ascii_digit()) {
+                    WILDCARD.to_owned()
+                } else {
+                    token.to_owned()
+                }
+            })
+            .collect();
+        let time = record.timestamp().unwrap_or(record.arrived_at());
+
+        let key = (tokens.len(), tokens.first().cloned().unwrap_or_default());
+        let candidates = self.groups.entry(key).or_default();
+        let best = candidates
+            .iter()
+            .map(|id| (*id, self.patterns[*id].similarity(&tokens)))
+            .filter(|(_, (similarity, _))| *similarity >= SIMILARITY)
+            .max_by(|(_, (a, a_wildcards)), (_, (b, b_wildcards))| {
+                a.total_cmp(b).then(a_wildcards.cmp(b_wildcards))
+            });
+
+        match best {
+            Some((id, _)) => {
+                let pattern = &mut self.patterns[id];
+                for (existing, token) in pattern.tokens.iter_mut().zip(tokens) {
+                    if *existing != token {
+                        *existing = WILDCARD.to_owned();
+                    }
+                }
+                pattern.count += 1;
+                pattern.first = pattern.first.min(time);
+                pattern.last = pattern.last.max(time);
+                id
+            }
+            None => {
+                let id = self.patterns.len();
+                candidates.push(id);
+                self.patterns.push(Pattern {
+                    tokens,
+                    count: 1,
+                    first: time,
+                    last: time,
+                    sample: text,
+                });
+                id
+            }
+        }
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Pattern> {
+        self.patterns.get(id)
+    }
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.patterns.clear();
+        self.groups.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::domain::parser;
+
+    fn add(patterns: &mut Patterns, line: &str) -> usize {
+        let mut record = Record::from(line);
+        parser::parse(&mut record, &[]);
+        patterns.add(&record)
+    }
+
+    #[test]
+    fn masks_tokens_with_digits_from_the_start() {
+        let mut patterns = Patterns::default();
+        let id = add(&mut patterns, "user 42 logged in from 10.0.0.1");
+        assert_eq!(
+            patterns.get(id).unwrap().template(),
+            "user <*> logged in from <*>"
+        );
+        assert_eq!(
+            patterns.get(id).unwrap().sample(),
+            "user 42 logged in from 10.0.0.1"
+        );
+    }
+
+    #[test]
+    fn joins_similar_messages_and_masks_differing_words() {
+        let mut patterns = Patterns::default();
+        let first = add(&mut patterns, "connection to db timed out");
+        let second = add(&mut patterns, "connection to cache timed out");
+        assert_eq!(first, second);
+        let pattern = patterns.get(first).unwrap();
+        assert_eq!(pattern.template(), "connection to <*> timed out");
+        assert_eq!(pattern.count(), 2);
+
+        // another word count or first word is another pattern
+        let longer = add(&mut patterns, "connection to db timed out again");
+        let other = add(&mut patterns, "request to db timed out");
+        assert_ne!(longer, first);
+        assert_ne!(other, first);
+        // too few equal words
+        let different = add(&mut patterns, "connection refused by remote host");
+        assert_ne!(different, first);
+        assert_eq!(patterns.len(), 4);
+    }
+
+    #[test]
+    fn clusters_the_message_field_of_structured_records() {
+        let mut patterns = Patterns::default();
+        let first = add(
+            &mut patterns,
+            r#"{"level":"info","msg":"order 17 created"}"#,
+        );
+        let second = add(&mut patterns, "level=warn msg=\"order 18 created\"");
+        assert_eq!(first, second);
+        assert_eq!(patterns.get(first).unwrap().template(), "order <*> created");
+    }
+}
@@ -1,11 +1,128 @@
 use std::fmt;
 use std::str::FromStr;
+use std::time::SystemTime;
+
+use super::level::Level;
+
+/// Keys commonly used by structured loggers.
+pub const TIME_KEYS: [&str; 5] = ["ts", "time", "timestamp", "@timestamp", "t"];
+pub const LEVEL_KEYS: [&str; 5] = ["level", "lvl", "severity", "loglevel", "log.level"];
+pub const MESSAGE_KEYS: [&str; 4] = ["msg", "message", "@message", "text"];
+
+/// Structure detected in the record on ingestion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Format {
+    #[default]
+    Plain,
+    Json,
+    Logfmt,
+    /// Parsed by a user-defined rule
+    Custom,
+}
+
+/// Which time of a record to go by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeSource {
+    /// When the record arrived
+    #[default]
+    Arrival,
+    /// Parsed from the record text, the arrival time if there is none
+    Parsed,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Record(String);
+pub struct Record {
+    value: String,
+    arrived_at: SystemTime,
+    source: String,
+    format: Format,
+    fields: Vec<(String, String)>,
+    level: Option<Level>,
+    timestamp: Option<SystemTime>,
+    pattern: Option<u32>,
+}
 impl Record {
     pub fn new(v: String) -> Self {
-        Self(v)
+        Self {
+            value: v,
+            arrived_at: SystemTime::now(),
+            source: String::new(),
+            format: Format::Plain,
+            fields: vec![],
+            level: None,
+            timestamp: None,
+            pattern: None,
+        }
+    }
+    /// Overrides the arrival time, e.g. with the one stored in a capture file.
+    pub fn with_arrived_at(mut self, arrived_at: SystemTime) -> Self {
+        self.arrived_at = arrived_at;
+        self
+    }
+    /// Replaces the text, e.g. with a redacted one.
+    pub fn set_value(&mut self, value: String) {
+        self.value = value;
+    }
+    pub fn arrived_at(&self) -> SystemTime {
+        self.arrived_at
+    }
+    /// Name of the source the record came from (`stdin`, a command, …).
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = source.to_owned();
+        self
+    }
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+    pub fn format(&self) -> Format {
+        self.format
+    }
+    /// Parsed fields in their original order, empty for plain records.
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+    pub fn set_fields(&mut self, format: Format, fields: Vec<(String, String)>) {
+        self.format = format;
+        self.fields = fields;
+    }
+    pub fn level(&self) -> Option<Level> {
+        self.level
+    }
+    pub fn set_level(&mut self, level: Option<Level>) {
+        self.level = level;
+    }
+    /// Time the record was written at, parsed from its text.
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.timestamp
+    }
+    pub fn set_timestamp(&mut self, timestamp: Option<SystemTime>) {
+        self.timestamp = timestamp;
+    }
+    pub fn time(&self, source: TimeSource) -> SystemTime {
+        match source {
+            TimeSource::Arrival => self.arrived_at,
+            TimeSource::Parsed => self.timestamp.unwrap_or(self.arrived_at),
+        }
+    }
+    /// Id of the pattern the record belongs to, see [`super::patterns::Patterns`].
+    pub fn pattern(&self) -> Option<usize> {
+        self.pattern.map(|id| id as usize)
+    }
+    pub fn set_pattern(&mut self, pattern: Option<usize>) {
+        // u32 keeps records (and events carrying them) small
+        self.pattern = pattern.map(|id| id as u32);
+    }
+    /// First well-known field among `keys`, as `(key, value)`.
+    pub fn find_field<'a>(&'a self, keys: &[&'a str]) -> Option<(&'a str, &'a str)> {
+        keys.iter()
+            .find_map(|key| self.field(key).map(|value| (*key, value)))
     }
 }
 impl Default for Record {
@@ -15,17 +132,17 @@ impl Default for Record {
 }
 impl fmt::Display for Record {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.value)
     }
 }
 impl From<String> for Record {
     fn from(raw: String) -> Self {
-        Self(raw)
+        Self::new(raw)
     }
 }
 impl From<&str> for Record {
     fn from(raw: &str) -> Self {
-        Self(raw.to_owned())
+        Self::new(raw.to_owned())
     }
 }
 impl FromStr for Record {
@@ -37,6 +154,6 @@ impl FromStr for Record {
 }
 impl AsRef<str> for Record {
     fn as_ref(&self) -> &str {
-        &self.0
+        &self.value
     }
 }
@@ -1,7 +1,16 @@
 use std;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Range;
+use std::time::{Duration, SystemTime};
 
+use super::extractor::Extractor;
 use super::filter_key::FilterKey;
+use super::level::Level;
+use super::parser::{self, Rule};
+use super::patterns::Patterns;
 use super::record::Record;
+use super::redact::Redactor;
 
 #[derive(thiserror::Error, Debug)]
 pub enum RecordsError {
@@ -9,10 +18,61 @@ pub enum RecordsError {
     IO(#[from] std::io::Error),
 }
 
+/// What records are shown, every pane has its own.
+#[derive(Clone, Default)]
+pub struct Filter {
+    key: FilterKey,
+    min_level: Option<Level>,
+    values: Vec<(Extractor, String)>,
+    /// Show only members of that pattern.
+    shown_pattern: Option<usize>,
+    hidden_patterns: HashSet<usize>,
+    /// Show only bookmarked records.
+    only_bookmarked: bool,
+    /// Show only records of that source.
+    source: Option<String>,
+}
+
+impl Filter {
+    /// Records of `source` only.
+    pub fn source(source: &str) -> Self {
+        Self {
+            source: Some(source.to_owned()),
+            ..Default::default()
+        }
+    }
+    pub fn source_name(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+    pub fn key(&self) -> &FilterKey {
+        &self.key
+    }
+
+    /// Drops the parts referring to records, when they are cleared.
+    pub fn forget_records(&mut self) {
+        self.shown_pattern = None;
+        self.hidden_patterns.clear();
+        self.only_bookmarked = false;
+    }
+}
+
 #[derive(Clone)]
 pub struct Records {
     data: Vec<Record>,
-    filter_key: FilterKey,
+    filter: Filter,
+    level_counts: [usize; Level::ALL.len()],
+    keys: Vec<String>,
+    known_keys: HashSet<String>,
+    rules: Vec<Rule>,
+    patterns: Patterns,
+    /// Arrival of the latest record of every source.
+    last_arrivals: HashMap<String, SystemTime>,
+    /// Level of the latest record of every source, for its continuation lines.
+    last_levels: HashMap<String, Option<Level>>,
+    /// Indexes of bookmarked records.
+    bookmarks: BTreeSet<usize>,
+    /// Secrets masker the search runs against, `None` while secrets are revealed.
+    redactor: Option<Redactor>,
 }
 
 impl Default for Records {
@@ -25,42 +85,331 @@ impl Records {
     pub fn new() -> Self {
         Self {
             data: Vec::new(),
-            filter_key: FilterKey::default(),
+            filter: Filter::default(),
+            level_counts: [0; Level::ALL.len()],
+            keys: Vec::new(),
+            known_keys: HashSet::new(),
+            rules: Vec::new(),
+            patterns: Patterns::default(),
+            last_arrivals: HashMap::new(),
+            last_levels: HashMap::new(),
+            bookmarks: BTreeSet::new(),
+            redactor: None,
         }
     }
 
-    pub fn add(&mut self, val: Record) {
+    pub fn add(&mut self, mut val: Record) {
+        parser::parse(&mut val, &self.rules);
+        if let Some(level) = val.level() {
+            self.level_counts[level as usize] += 1;
+        } else if val.as_ref().starts_with(char::is_whitespace) {
+            // continuation of a multi-line record (e.g. a stack trace) of the same source,
+            // filtered along with it but not counted
+            val.set_level(self.last_levels.get(val.source()).copied().flatten());
+        }
+        self.last_levels
+            .insert(val.source().to_owned(), val.level());
+        self.last_arrivals
+            .insert(val.source().to_owned(), val.arrived_at());
+        let pattern = self.patterns.add(&val);
+        val.set_pattern(Some(pattern));
+        for (key, _) in val.fields() {
+            if !self.known_keys.contains(key) {
+                self.known_keys.insert(key.clone());
+                self.keys.push(key.clone());
+            }
+        }
         self.data.push(val);
     }
+    /// Searches the text with secrets masked, so typing a secret doesn't find it.
+    pub fn set_redactor(&mut self, redactor: Option<Redactor>) {
+        self.redactor = redactor;
+    }
+    /// User-defined parsers, applied to records added from now on.
+    pub fn set_rules(&mut self, rules: Vec<Rule>) {
+        self.rules = rules;
+    }
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.level_counts = [0; Level::ALL.len()];
+        self.keys.clear();
+        self.known_keys.clear();
+        self.patterns.clear();
+        self.filter.forget_records();
+        self.last_arrivals.clear();
+        self.last_levels.clear();
+        self.bookmarks.clear();
+    }
     pub fn len(&self) -> usize {
         self.data.len()
     }
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
     pub fn len_filtered(&self) -> usize {
-        self.data
-            .iter()
-            .filter(|&r| r.to_string().contains(&self.filter_key.to_string()))
-            .count()
+        self.iter().count()
+    }
+    /// Field names of structured records, in the order they were first seen.
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+    /// Number of records of every level, in [`Level::ALL`] order.
+    pub fn level_counts(&self) -> [usize; Level::ALL.len()] {
+        self.level_counts
+    }
+
+    pub fn filter(&self) -> &Filter {
+        &self.filter
+    }
+    /// Puts `filter` in place of the current one, which `filter` gets.
+    pub fn swap_filter(&mut self, filter: &mut Filter) {
+        std::mem::swap(&mut self.filter, filter);
     }
 
     pub fn filter_key(&self) -> &FilterKey {
-        &self.filter_key
+        &self.filter.key
     }
     pub fn set_filter_key(&mut self, key: FilterKey) {
-        self.filter_key = key;
+        self.filter.key = key;
+    }
+    pub fn min_level(&self) -> Option<Level> {
+        self.filter.min_level
+    }
+    /// Hides records below `level`, records without a level are always shown.
+    pub fn set_min_level(&mut self, level: Option<Level>) {
+        self.filter.min_level = level;
+    }
+
+    pub fn value_filters(&self) -> &[(Extractor, String)] {
+        &self.filter.values
+    }
+    /// Shows only records where `extractor` gives `value`, on top of other filters.
+    pub fn add_value_filter(&mut self, extractor: Extractor, value: String) {
+        self.filter.values.push((extractor, value));
+    }
+    /// Drops the most recently added value filter.
+    pub fn remove_value_filter(&mut self) {
+        self.filter.values.pop();
+    }
+
+    /// The `k` most frequent values of `extractor` among filtered records,
+    /// with the number of filtered records.
+    pub fn breakdown(&self, extractor: &Extractor, k: usize) -> (Vec<(String, usize)>, usize) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut total = 0;
+        for record in self.iter() {
+            total += 1;
+            if let Some(value) = extractor.extract(record) {
+                *counts.entry(value.into_owned()).or_default() += 1;
+            }
+        }
+        let mut values: Vec<_> = counts.into_iter().collect();
+        values.sort_by(|(a_value, a), (b_value, b)| b.cmp(a).then(a_value.cmp(b_value)));
+        values.truncate(k);
+        (values, total)
+    }
+
+    /// Sources with the arrival of their latest record.
+    pub fn last_arrivals(&self) -> &HashMap<String, SystemTime> {
+        &self.last_arrivals
+    }
+    pub fn patterns(&self) -> &Patterns {
+        &self.patterns
+    }
+    pub fn shown_pattern(&self) -> Option<usize> {
+        self.filter.shown_pattern
+    }
+    pub fn hidden_patterns(&self) -> &HashSet<usize> {
+        &self.filter.hidden_patterns
+    }
+    /// Shows only members of the pattern, again to show everything.
+    pub fn toggle_shown_pattern(&mut self, pattern: usize) {
+        if self.filter.shown_pattern == Some(pattern) {
+            self.filter.shown_pattern = None;
+        } else {
+            self.filter.shown_pattern = Some(pattern);
+            self.filter.hidden_patterns.remove(&pattern);
+        }
+    }
+    /// Hides members of the pattern, again to show them back.
+    pub fn toggle_hidden_pattern(&mut self, pattern: usize) {
+        if !self.filter.hidden_patterns.remove(&pattern) {
+            self.filter.hidden_patterns.insert(pattern);
+            if self.filter.shown_pattern == Some(pattern) {
+                self.filter.shown_pattern = None;
+            }
+        }
+    }
+    pub fn clear_pattern_filters(&mut self) {
+        self.filter.shown_pattern = None;
+        self.filter.hidden_patterns.clear();
+    }
+
+    pub fn bookmarks(&self) -> &BTreeSet<usize> {
+        &self.bookmarks
+    }
+    pub fn is_bookmarked(&self, index: usize) -> bool {
+        self.bookmarks.contains(&index)
+    }
+    pub fn toggle_bookmark(&mut self, index: usize) {
+        if index < self.data.len() && !self.bookmarks.remove(&index) {
+            self.bookmarks.insert(index);
+        }
+    }
+    pub fn only_bookmarked(&self) -> bool {
+        self.filter.only_bookmarked
+    }
+    pub fn toggle_only_bookmarked(&mut self) {
+        self.filter.only_bookmarked = !self.filter.only_bookmarked;
+    }
+
+    /// Arrivals per `bucket` over the last `buckets` buckets before `now`,
+    /// all and matching records.
+    pub fn rate(&self, bucket: Duration, buckets: usize, now: SystemTime) -> (Vec<u64>, Vec<u64>) {
+        let mut all = vec![0; buckets];
+        let mut matching = vec![0; buckets];
+        // records are stored in arrival order
+        for (index, record) in self.data.iter().enumerate().rev() {
+            let age = now.duration_since(record.arrived_at()).unwrap_or_default();
+            let back = (age.as_secs_f64() / bucket.as_secs_f64()) as usize;
+            if back >= buckets {
+                break;
+            }
+            all[buckets - 1 - back] += 1;
+            if self.matches(index, record) {
+                matching[buckets - 1 - back] += 1;
+            }
+        }
+        (all, matching)
+    }
+
+    /// Records per bucket over the whole buffer, all and matching, and the time span.
+    ///
+    /// `parsed` uses timestamps from the text, records without one
+    /// (e.g. stack trace lines) take the time of the previous record.
+    pub fn histogram(
+        &self,
+        buckets: usize,
+        parsed: bool,
+    ) -> (Vec<u64>, Vec<u64>, Option<Range<SystemTime>>) {
+        let mut times = Vec::with_capacity(self.data.len());
+        let mut last = None;
+        for record in &self.data {
+            let time = if parsed {
+                record.timestamp().or(last).unwrap_or(record.arrived_at())
+            } else {
+                record.arrived_at()
+            };
+            last = Some(time);
+            times.push(time);
+        }
+
+        let mut all = vec![0; buckets];
+        let mut matching = vec![0; buckets];
+        let (Some(start), Some(end)) = (times.iter().min(), times.iter().max()) else {
+            return (all, matching, None);
+        };
+        let span = end.duration_since(*start).unwrap_or_default().as_secs_f64();
+        for (index, (record, time)) in self.data.iter().zip(&times).enumerate() {
+            let offset = time
+                .duration_since(*start)
+                .unwrap_or_default()
+                .as_secs_f64();
+            let bucket = if span > 0.0 {
+                ((offset / span * buckets as f64) as usize).min(buckets.saturating_sub(1))
+            } else {
+                0
+            };
+            if let Some(count) = all.get_mut(bucket) {
+                *count += 1;
+                if self.matches(index, record) {
+                    matching[bucket] += 1;
+                }
+            }
+        }
+        (all, matching, Some(*start..*end))
+    }
+
+    /// Filtered records with their index, which identifies them until [`Records::clear`].
+    pub fn indexed(&self) -> impl Iterator<Item = (usize, &Record)> {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(index, record)| self.matches(*index, record))
+    }
+    pub fn get(&self, index: usize) -> Option<&Record> {
+        self.data.get(index)
+    }
+    /// Whether the record at `index` passes the filters.
+    pub fn is_shown(&self, index: usize) -> bool {
+        self.get(index)
+            .is_some_and(|record| self.matches(index, record))
     }
 
     pub fn iter(&self) -> RecordsIterator<'_> {
         RecordsIterator {
-            data: &self.data,
-            filter_key: &self.filter_key,
+            records: self,
             index: 0,
         }
     }
+
+    /// Whether the record at `index` passes `filter` rather than the current filter.
+    pub fn matches_filter(&self, filter: &Filter, index: usize) -> bool {
+        self.get(index)
+            .is_some_and(|record| self.matches_with(filter, index, record))
+    }
+
+    fn matches(&self, index: usize, record: &Record) -> bool {
+        self.matches_with(&self.filter, index, record)
+    }
+
+    fn matches_with(&self, filter: &Filter, index: usize, record: &Record) -> bool {
+        if filter.only_bookmarked && !self.bookmarks.contains(&index) {
+            return false;
+        }
+
+        if let Some(source) = &filter.source
+            && record.source() != source
+        {
+            return false;
+        }
+
+        if let (Some(min_level), Some(level)) = (filter.min_level, record.level())
+            && level < min_level
+        {
+            return false;
+        }
+
+        if let Some(pattern) = record.pattern()
+            && (filter.shown_pattern.is_some_and(|shown| shown != pattern)
+                || filter.hidden_patterns.contains(&pattern))
+        {
+            return false;
+        }
+
+        let values_match = filter
+            .values
+            .iter()
+            .all(|(extractor, value)| extractor.extract(record).is_some_and(|v| v == *value));
+        if !values_match {
+            return false;
+        }
+
+        if filter.key.as_ref() == "" {
+            return true;
+        }
+
+        let text = match &self.redactor {
+            Some(redactor) => redactor.redact(record.as_ref()),
+            None => Cow::Borrowed(record.as_ref()),
+        };
+        text.to_lowercase()
+            .contains(&filter.key.as_ref().to_lowercase())
+    }
 }
 
 pub struct RecordsIterator<'a> {
-    data: &'a Vec<Record>,
-    filter_key: &'a FilterKey,
+    records: &'a Records,
     index: usize,
 }
 impl<'a> Iterator for RecordsIterator<'a> {
@@ -68,29 +417,69 @@ impl<'a> Iterator for RecordsIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            if self.data.is_empty() {
+            if self.records.data.is_empty() {
                 return None;
             }
 
-            if self.index == self.data.len() {
+            if self.index == self.records.data.len() {
                 return None;
             }
 
-            let result = &self.data[self.index];
+            let index = self.index;
+            let result = &self.records.data[index];
 
             self.index += 1;
 
-            if self.filter_key.as_ref() == "" {
-                return Some(result);
-            }
-
-            if result
-                .as_ref()
-                .to_lowercase()
-                .contains(&self.filter_key.as_ref().to_lowercase())
-            {
+            if self.records.matches(index, result) {
                 return Some(result);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuation_lines_take_the_level_of_their_source() {
+        let mut records = Records::new();
+        records.add(Record::new("ERROR thread 'main' panicked".to_owned()).with_source("b"));
+        records.add(Record::new("INFO request completed".to_owned()).with_source("a"));
+        records.add(Record::new("    at app::main (src/main.rs:27)".to_owned()).with_source("b"));
+        records.add(Record::new("    at app::serve (src/serve.rs:8)".to_owned()).with_source("a"));
+
+        let levels: Vec<_> = records.iter().map(Record::level).collect();
+        assert_eq!(
+            levels,
+            [
+                Some(Level::Error),
+                Some(Level::Info),
+                Some(Level::Error),
+                Some(Level::Info)
+            ]
+        );
+        // continuation lines aren't counted
+        let counts = records.level_counts();
+        assert_eq!(counts[Level::Error as usize], 1);
+        assert_eq!(counts[Level::Info as usize], 1);
+    }
+
+    #[test]
+    fn search_skips_masked_secrets() {
+        let mut records = Records::new();
+        records.add(Record::new("login alice@example.com".to_owned()));
+        records.add(Record::new("login bob".to_owned()));
+        records.set_redactor(Some(Redactor::builtin()));
+
+        records.set_filter_key("alice".into());
+        assert_eq!(records.len_filtered(), 0);
+        records.set_filter_key("login".into());
+        assert_eq!(records.len_filtered(), 2);
+
+        // revealed
+        records.set_redactor(None);
+        records.set_filter_key("alice".into());
+        assert_eq!(records.len_filtered(), 1);
+    }
+}
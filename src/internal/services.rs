@@ -1 +1,6 @@
+pub mod capture;
+pub mod clipboard;
+pub mod config;
+pub mod generator;
+pub mod replay;
 pub mod tui;
@@ -0,0 +1,132 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::internal::domain::record::Record;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CaptureError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+}
+
+/// First line of a capture which keeps the record sources.
+///
+/// Captures without it are from older versions, their lines have no source.
+const HEADER: &str = "#lf-capture 2";
+
+/// Writes records together with their arrival time and source, one per line:
+/// `<unix millis>\t<source>\t<record>`.
+pub struct Capture {
+    writer: BufWriter<File>,
+}
+
+impl Capture {
+    pub fn create(path: &Path) -> Result<Self, CaptureError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", HEADER)?;
+        writer.flush()?;
+        Ok(Self { writer })
+    }
+
+    pub fn write(&mut self, record: &Record) -> Result<(), CaptureError> {
+        let millis = record
+            .arrived_at()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let value = record.to_string();
+        writeln!(
+            self.writer,
+            "{}\t{}\t{}",
+            millis,
+            record.source().replace('\t', " "),
+            value.trim_end_matches(['\n', '\r'])
+        )?;
+        // flush every record, so the capture survives the app being killed
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads a capture file back into records.
+///
+/// Lines without a timestamp prefix inherit the time of the previous line,
+/// so a plain log file can be replayed as well (all at once).
+/// Records of older captures and plain files get the `replay` source.
+pub fn read_capture(path: &Path) -> Result<Vec<Record>, CaptureError> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut lines = reader.lines().peekable();
+    let with_sources = matches!(lines.peek(), Some(Ok(first)) if first == HEADER);
+    if with_sources {
+        lines.next();
+    }
+    let mut records = vec![];
+    let mut last = UNIX_EPOCH;
+    for line in lines {
+        let line = line?;
+        let (arrived_at, source, value) = match parse_line(&line, with_sources) {
+            Some((millis, source, value)) => {
+                (UNIX_EPOCH + Duration::from_millis(millis), source, value)
+            }
+            None => (last, "replay", line.as_str()),
+        };
+        last = arrived_at;
+        records.push(
+            Record::new(value.to_owned())
+                .with_arrived_at(arrived_at)
+                .with_source(source),
+        );
+    }
+    Ok(records)
+}
+
+/// Splits a captured line into its time, source and record.
+fn parse_line(line: &str, with_source: bool) -> Option<(u64, &str, &str)> {
+    let (millis, rest) = line.split_once('\t')?;
+    let millis = millis.parse().ok()?;
+    if !with_source {
+        return Some((millis, "replay", rest));
+    }
+    let (source, value) = rest.split_once('\t')?;
+    Some((millis, source, value))
+}
+
+/// Offset of every record from the first one.
+///
+/// Offsets never decrease, so a record captured out of order is replayed
+/// right after its predecessor.
+pub fn offsets(records: &[Record]) -> Vec<Duration> {
+    let start = records
+        .first()
+        .map(|r| r.arrived_at())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut last = Duration::ZERO;
+    records
+        .iter()
+        .map(|r| {
+            last = last.max(r.arrived_at().duration_since(start).unwrap_or_default());
+            last
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lines_with_and_without_source() {
+        assert_eq!(
+            parse_line("1000\tping\tPING yandex.ru", true),
+            Some((1000, "ping", "PING yandex.ru"))
+        );
+        // older captures, the record may have tabs
+        assert_eq!(
+            parse_line("1000\tPING\tyandex.ru", false),
+            Some((1000, "replay", "PING\tyandex.ru"))
+        );
+        assert_eq!(parse_line("plain line", true), None);
+    }
+}
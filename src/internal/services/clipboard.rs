@@ -0,0 +1,138 @@
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ClipboardError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error("{0} bytes is too much for OSC 52, turn on `clipboard.file`")]
+    TooLarge(usize),
+    #[error("clipboard is turned off in the config")]
+    Disabled,
+}
+
+/// Terminals drop longer OSC 52 sequences, xterm and tmux among them.
+const OSC52_LIMIT: usize = 100_000;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Where a copy went.
+#[derive(Debug, Default)]
+pub struct Copied {
+    /// Sent to the terminal with OSC 52.
+    pub osc52: bool,
+    pub file: Option<PathBuf>,
+}
+
+/// Copies text to the system clipboard with the OSC 52 escape sequence,
+/// so it works over SSH, optionally also to a temp file.
+#[derive(Debug, Clone)]
+pub struct Clipboard {
+    osc52: bool,
+    file: bool,
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self {
+            osc52: true,
+            file: false,
+        }
+    }
+}
+
+impl Clipboard {
+    pub fn new(osc52: bool, file: bool) -> Self {
+        Self { osc52, file }
+    }
+
+    pub fn copy(&self, text: &str) -> Result<Copied, ClipboardError> {
+        let mut copied = Copied::default();
+        let encoded = base64(text.as_bytes());
+        if self.osc52 && encoded.len() <= OSC52_LIMIT {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(osc52(&encoded).as_bytes())?;
+            stdout.flush()?;
+            copied.osc52 = true;
+        }
+        if self.file {
+            copied.file = Some(write_temp_file(text)?);
+        }
+
+        match copied {
+            Copied {
+                osc52: false,
+                file: None,
+            } if self.osc52 => Err(ClipboardError::TooLarge(text.len())),
+            Copied {
+                osc52: false,
+                file: None,
+            } => Err(ClipboardError::Disabled),
+            copied => Ok(copied),
+        }
+    }
+}
+
+/// The escape sequence setting the clipboard, wrapped for tmux to pass it on
+/// (tmux 3.3+ needs `set -g allow-passthrough on`).
+fn osc52(encoded: &str) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", encoded);
+    if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// Writes `text` to a new file only the user can read.
+///
+/// The file must not exist yet, so a link planted at the path isn't followed.
+fn write_temp_file(text: &str) -> Result<PathBuf, ClipboardError> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let mut attempt = 0;
+    loop {
+        let path = std::env::temp_dir().join(format!(
+            "lf-copy-{}-{}-{}.log",
+            std::process::id(),
+            millis,
+            attempt
+        ));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists && attempt < 16 => attempt += 1,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
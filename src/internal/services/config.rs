@@ -0,0 +1,456 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use ratatui::style::{Color, Modifier, Style};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::internal::domain::extractor::Extractor;
+use crate::internal::domain::parser::Rule;
+use crate::internal::domain::record::TimeSource;
+use crate::internal::domain::redact::Redactor;
+use crate::internal::services::tui::keymap::{Action, KeyBinding, KeyBindingError, Keymap, Preset};
+use crate::internal::services::tui::render::Highlight;
+use crate::internal::services::tui::theme::Theme;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("reading config {0}: {1}")]
+    IO(PathBuf, std::io::Error),
+    #[error("parsing config {0}: {1}")]
+    Toml(PathBuf, toml::de::Error),
+    #[error("`{0}`: {1} isn't a number of seconds")]
+    Seconds(String, f64),
+    #[error("pattern `{0}`: {1}")]
+    Regex(String, regex::Error),
+    #[error("highlight `{0}`: unknown color `{1}`")]
+    Color(String, String),
+    #[error("keys: unknown action `{0}`")]
+    Action(String),
+    #[error("keys: `{0}`: {1}")]
+    Key(String, KeyBindingError),
+    #[error("keys: {0}")]
+    KeyConflicts(String),
+    #[error("unknown theme `{0}`, built-in ones are dark, light, high-contrast and monochrome")]
+    Theme(String),
+    #[error("theme `{0}`: unknown style `{1}`")]
+    ThemeStyle(String, String),
+    #[error("theme `{0}`: unknown color `{1}`")]
+    ThemeColor(String, String),
+}
+
+/// User settings, read from `$XDG_CONFIG_HOME/lf/config.toml`
+/// (`~/.config/lf/config.toml`) or the file given with `--config`.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub parsers: Vec<ParserConfig>,
+    pub captures: Vec<CaptureConfig>,
+    pub redaction: RedactionConfig,
+    pub highlights: Vec<HighlightConfig>,
+    pub gaps: GapsConfig,
+    pub clipboard: ClipboardConfig,
+    pub keys: KeysConfig,
+    pub theme: ThemeConfig,
+    pub themes: BTreeMap<String, UserThemeConfig>,
+}
+
+/// A named-capture pattern turning a bespoke format into fields.
+///
+/// ```toml
+/// [[parsers]]
+/// name = "legacy"
+/// pattern = '^\[(?P<ts>[^\]]+)\]\[(?P<thread>[^\]]+)\] (?P<level>\w+) (?P<msg>.*)$'
+/// sources = ["legacy-app"]
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParserConfig {
+    pub name: String,
+    pub pattern: String,
+    /// Sources the parser applies to, all of them when empty.
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+/// A value to break records down by, next to parsed fields.
+///
+/// ```toml
+/// [[captures]]
+/// name = "user"
+/// pattern = 'user=(\w+)'
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CaptureConfig {
+    pub name: String,
+    /// The first capture group is the value, the whole match if there is none.
+    pub pattern: String,
+}
+
+/// Style for every match of a pattern in the logs.
+///
+/// ```toml
+/// [[highlights]]
+/// pattern = 'timed out|refused'
+/// fg = "red"
+/// bold = true
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HighlightConfig {
+    pub pattern: String,
+    /// A name (`red`, `lightblue`), an index (`208`) or `#rrggbb`.
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    #[serde(default)]
+    pub bold: bool,
+}
+
+/// Separators between records far apart in time and alerts on silent sources.
+///
+/// ```toml
+/// [gaps]
+/// threshold = 30
+/// time = "parsed"
+/// silence_alert = 60
+/// ```
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GapsConfig {
+    /// Seconds between records to show a separator.
+    pub threshold: f64,
+    pub time: TimeSource,
+    /// Seconds without records from a source to warn about it.
+    pub silence_alert: Option<f64>,
+}
+
+impl GapsConfig {
+    /// Gap to show a separator at, none when the threshold isn't positive.
+    pub fn threshold(&self) -> Result<Option<Duration>, ConfigError> {
+        seconds("gaps.threshold", self.threshold)
+    }
+
+    pub fn silence_alert(&self) -> Result<Option<Duration>, ConfigError> {
+        match self.silence_alert {
+            Some(secs) => seconds("gaps.silence_alert", secs),
+            None => Ok(None),
+        }
+    }
+}
+
+/// `secs` of a setting as a duration, none when it isn't positive.
+fn seconds(setting: &str, secs: f64) -> Result<Option<Duration>, ConfigError> {
+    if secs.is_nan() || secs <= 0.0 {
+        return Ok(None);
+    }
+    Duration::try_from_secs_f64(secs)
+        .map(Some)
+        .map_err(|_| ConfigError::Seconds(setting.to_owned(), secs))
+}
+
+impl Default for GapsConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 30.0,
+            time: TimeSource::Arrival,
+            silence_alert: None,
+        }
+    }
+}
+
+/// Secrets masked on screen and in capture files.
+///
+/// ```toml
+/// [redaction]
+/// enabled = true
+///
+/// [[redaction.patterns]]
+/// name = "session"
+/// pattern = 'sid=(\w+)'
+/// ```
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Masked next to the built-in secrets, only the first capture group if there is one.
+    pub patterns: Vec<CaptureConfig>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: vec![],
+        }
+    }
+}
+
+/// How copied records reach the clipboard.
+///
+/// ```toml
+/// [clipboard]
+/// osc52 = true
+/// file = true
+/// ```
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClipboardConfig {
+    /// Send to the terminal with the OSC 52 escape sequence.
+    pub osc52: bool,
+    /// Also write to a file in the temp dir, for terminals without OSC 52.
+    pub file: bool,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            osc52: true,
+            file: false,
+        }
+    }
+}
+
+/// Key bindings on top of a preset, by action name.
+///
+/// ```toml
+/// [keys]
+/// preset = "vim"
+///
+/// [keys.bindings]
+/// quit = ["ctrl+c", "f10"]
+/// bookmark = "alt+b"
+/// ```
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub preset: Preset,
+    pub bindings: BTreeMap<String, Keys>,
+}
+
+/// One key or a list of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Theme to use, a built-in one or one of `[themes]`.
+///
+/// ```toml
+/// [theme]
+/// name = "light"
+/// ```
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub name: String,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            name: "dark".to_owned(),
+        }
+    }
+}
+
+/// A built-in theme with some of its styles changed.
+///
+/// ```toml
+/// [themes.solarized]
+/// base = "light"
+/// accent = { fg = "#268bd2", bold = true }
+/// found = { fg = "black", bg = "#b58900" }
+/// ```
+#[derive(Deserialize)]
+pub struct UserThemeConfig {
+    #[serde(default = "default_base")]
+    pub base: String,
+    /// Styles by name: `text`, `muted`, `accent`, `value`, `number`, `found`, `selected`,
+    /// `panel`, `selection`, `cursor`, `alert`, `badge` and the levels `trace` … `fatal`.
+    #[serde(flatten)]
+    pub styles: BTreeMap<String, StyleConfig>,
+}
+
+fn default_base() -> String {
+    "dark".to_owned()
+}
+
+/// Replaces a style of the base theme.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StyleConfig {
+    /// A name (`red`, `lightblue`), an index (`208`) or `#rrggbb`.
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub dim: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub underlined: bool,
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+impl Config {
+    /// Loads `path`, or the default config file if it exists.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let path = match path {
+            Some(path) => path.to_owned(),
+            None => match default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Config::default()),
+            },
+        };
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| ConfigError::IO(path.clone(), e))?;
+        toml::from_str(&content).map_err(|e| ConfigError::Toml(path, e))
+    }
+
+    pub fn rules(&self) -> Result<Vec<Rule>, ConfigError> {
+        self.parsers
+            .iter()
+            .map(|parser| {
+                let regex = Regex::new(&parser.pattern)
+                    .map_err(|e| ConfigError::Regex(parser.name.clone(), e))?;
+                Ok(Rule::new(
+                    parser.name.clone(),
+                    regex,
+                    parser.sources.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    pub fn captures(&self) -> Result<Vec<Extractor>, ConfigError> {
+        self.captures
+            .iter()
+            .map(|capture| {
+                let regex = Regex::new(&capture.pattern)
+                    .map_err(|e| ConfigError::Regex(capture.name.clone(), e))?;
+                Ok(Extractor::Capture {
+                    name: capture.name.clone(),
+                    regex,
+                })
+            })
+            .collect()
+    }
+
+    pub fn redactor(&self) -> Result<Redactor, ConfigError> {
+        if !self.redaction.enabled {
+            return Ok(Redactor::default());
+        }
+        let mut redactor = Redactor::builtin();
+        for pattern in &self.redaction.patterns {
+            let regex = Regex::new(&pattern.pattern)
+                .map_err(|e| ConfigError::Regex(pattern.name.clone(), e))?;
+            redactor.add(pattern.name.clone(), regex);
+        }
+        Ok(redactor)
+    }
+
+    /// The preset with the configured bindings, failing on conflicting keys.
+    pub fn keymap(&self) -> Result<Keymap, ConfigError> {
+        let mut keymap = Keymap::preset(self.keys.preset);
+        for (name, keys) in &self.keys.bindings {
+            let action =
+                Action::from_name(name).ok_or_else(|| ConfigError::Action(name.clone()))?;
+            let keys = match keys {
+                Keys::One(key) => std::slice::from_ref(key),
+                Keys::Many(keys) => keys.as_slice(),
+            };
+            let keys = keys
+                .iter()
+                .map(|key| {
+                    key.parse::<KeyBinding>()
+                        .map_err(|e| ConfigError::Key(name.clone(), e))
+                })
+                .collect::<Result<_, _>>()?;
+            keymap.bind(action, keys);
+        }
+        let conflicts = keymap.conflicts();
+        if !conflicts.is_empty() {
+            return Err(ConfigError::KeyConflicts(conflicts.join(", ")));
+        }
+        Ok(keymap)
+    }
+
+    /// Theme called `name`, or the configured one.
+    pub fn theme(&self, name: Option<&str>) -> Result<Theme, ConfigError> {
+        let name = name.unwrap_or(&self.theme.name);
+        let Some(user) = self.themes.get(name) else {
+            return Theme::builtin(name).ok_or_else(|| ConfigError::Theme(name.to_owned()));
+        };
+        let mut theme =
+            Theme::builtin(&user.base).ok_or_else(|| ConfigError::Theme(user.base.clone()))?;
+        for (style_name, config) in &user.styles {
+            let style = theme
+                .style_mut(style_name)
+                .ok_or_else(|| ConfigError::ThemeStyle(name.to_owned(), style_name.clone()))?;
+            let color = |color: &str| {
+                color
+                    .parse::<Color>()
+                    .map_err(|_| ConfigError::ThemeColor(name.to_owned(), color.to_owned()))
+            };
+            let mut modifier = Modifier::empty();
+            for (on, flag) in [
+                (config.bold, Modifier::BOLD),
+                (config.dim, Modifier::DIM),
+                (config.italic, Modifier::ITALIC),
+                (config.underlined, Modifier::UNDERLINED),
+                (config.reversed, Modifier::REVERSED),
+            ] {
+                if on {
+                    modifier |= flag;
+                }
+            }
+            *style = Style::new().add_modifier(modifier);
+            if let Some(fg) = &config.fg {
+                *style = style.fg(color(fg)?);
+            }
+            if let Some(bg) = &config.bg {
+                *style = style.bg(color(bg)?);
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn highlights(&self) -> Result<Vec<Highlight>, ConfigError> {
+        self.highlights
+            .iter()
+            .map(|highlight| {
+                let regex = Regex::new(&highlight.pattern)
+                    .map_err(|e| ConfigError::Regex(highlight.pattern.clone(), e))?;
+                let color = |name: &str| {
+                    name.parse::<Color>()
+                        .map_err(|_| ConfigError::Color(highlight.pattern.clone(), name.to_owned()))
+                };
+                let mut style = Style::new();
+                if let Some(fg) = &highlight.fg {
+                    style = style.fg(color(fg)?);
+                }
+                if let Some(bg) = &highlight.bg {
+                    style = style.bg(color(bg)?);
+                }
+                if highlight.bold {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                Ok(Highlight { regex, style })
+            })
+            .collect()
+    }
+}
+
+fn default_path() -> Option<PathBuf> {
+    let dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(dir.join("lf").join("config.toml"))
+}
@@ -0,0 +1,294 @@
+use std::sync::mpsc::{SendError, Sender};
+use std::time::{Duration, SystemTime};
+
+use crate::Event;
+use crate::internal::domain::record::Record;
+
+const LEVELS: [(&str, &str, u64); 6] = [
+    // (name, ANSI color, weight)
+    ("TRACE", "\x1b[90m", 5),
+    ("DEBUG", "\x1b[36m", 20),
+    ("INFO", "\x1b[32m", 50),
+    ("WARN", "\x1b[33m", 15),
+    ("ERROR", "\x1b[31m", 8),
+    ("FATAL", "\x1b[1;31m", 2),
+];
+const SERVICES: [&str; 5] = ["api", "auth", "billing", "worker", "gateway"];
+const ENDPOINTS: [&str; 6] = [
+    "/v1/users",
+    "/v1/orders",
+    "/v1/login",
+    "/health",
+    "/v1/payments",
+    "/v1/search",
+];
+const MESSAGES: [&str; 10] = [
+    "request completed",
+    "user logged in",
+    "cache miss",
+    "retrying connection",
+    "connection to db-1 timed out",
+    "order created",
+    "payment declined",
+    "slow query detected",
+    "queue is almost full",
+    "config reloaded",
+];
+const WIDE_MESSAGES: [&str; 4] = [
+    "用户登录成功",
+    "🚀 deploy finished 🎉",
+    "ｆｕｌｌｗｉｄｔｈ ｔｅｘｔ",
+    "接続がタイムアウトしました",
+];
+const FRAMES: [&str; 5] = [
+    "at app::handlers::orders::create (src/handlers/orders.rs:42)",
+    "at app::db::pool::acquire (src/db/pool.rs:118)",
+    "at tokio::runtime::task::harness::poll (harness.rs:473)",
+    "at std::panicking::try (panicking.rs:552)",
+    "at app::main (src/main.rs:27)",
+];
+/// Virtual clock start, so generated lines don't depend on the current time.
+const EPOCH_SECS: u64 = 1_714_564_800; // 2024-05-01T12:00:00Z
+
+/// Small deterministic PRNG (splitmix64), good enough for fake data.
+struct Rng(u64);
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len() as u64) as usize]
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    Normal,
+    Burst,
+    Quiet,
+}
+
+pub struct GeneratorConfig {
+    /// Records per second in the normal phase.
+    pub rate: f64,
+    pub seed: u64,
+    /// Stop after that many records.
+    pub count: Option<usize>,
+    pub bursts: bool,
+}
+
+/// Produces a realistic synthetic log stream.
+///
+/// The same seed always produces the same lines in the same order.
+pub struct Generator {
+    config: GeneratorConfig,
+    rng: Rng,
+    clock: SystemTime,
+    phase: Phase,
+    phase_left: Duration,
+    produced: usize,
+}
+
+impl Generator {
+    pub fn new(config: GeneratorConfig) -> Self {
+        Self {
+            rng: Rng(config.seed),
+            config,
+            clock: SystemTime::UNIX_EPOCH + Duration::from_secs(EPOCH_SECS),
+            phase: Phase::Normal,
+            phase_left: Duration::from_secs(10),
+            produced: 0,
+        }
+    }
+
+    pub fn run(self, tx: Sender<Event>) -> Result<(), SendError<Event>> {
+        for (delay, lines) in self {
+            std::thread::sleep(delay);
+            for line in lines {
+                tx.send(Event::StdIn(Record::new(line).with_source("generate")))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_phase(&mut self) {
+        (self.phase, self.phase_left) = match self.phase {
+            Phase::Normal if self.config.bursts && self.rng.chance(50) => {
+                (Phase::Burst, Duration::from_secs(1 + self.rng.below(3)))
+            }
+            Phase::Normal if self.config.bursts => {
+                (Phase::Quiet, Duration::from_secs(3 + self.rng.below(10)))
+            }
+            _ => (Phase::Normal, Duration::from_secs(5 + self.rng.below(20))),
+        };
+    }
+
+    fn delay(&mut self) -> Duration {
+        let rate = match self.phase {
+            Phase::Normal => self.config.rate,
+            Phase::Burst => self.config.rate * 20.0,
+            Phase::Quiet => return self.phase_left,
+        };
+        // jitter the interval between 0.5x and 1.5x of the mean
+        let jitter = 0.5 + self.rng.below(1000) as f64 / 1000.0;
+        Duration::from_secs_f64(jitter / rate.max(0.001))
+    }
+
+    fn timestamp(&self) -> String {
+        chrono::DateTime::<chrono::Utc>::from(self.clock)
+            .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+            .to_string()
+    }
+
+    fn level(&mut self) -> (&'static str, &'static str) {
+        let total: u64 = LEVELS.iter().map(|(_, _, w)| w).sum();
+        let mut roll = self.rng.below(total);
+        for (name, color, weight) in LEVELS {
+            if roll < weight {
+                return (name, color);
+            }
+            roll -= weight;
+        }
+        (LEVELS[2].0, LEVELS[2].1)
+    }
+
+    fn message(&mut self) -> &'static str {
+        if self.rng.chance(10) {
+            self.rng.pick(&WIDE_MESSAGES)
+        } else {
+            self.rng.pick(&MESSAGES)
+        }
+    }
+
+    fn json_line(&mut self, level: &str) -> String {
+        let msg = self.message();
+        serde_json::json!({
+            "ts": self.timestamp(),
+            "level": level.to_lowercase(),
+            "service": self.rng.pick(&SERVICES),
+            "msg": msg,
+            "endpoint": self.rng.pick(&ENDPOINTS),
+            "user_id": 1000 + self.rng.below(50),
+            "latency_ms": self.rng.below(2000),
+        })
+        .to_string()
+    }
+
+    fn logfmt_line(&mut self, level: &str) -> String {
+        let msg = self.message();
+        format!(
+            "ts={} level={} service={} msg={:?} endpoint={} dur={}ms",
+            self.timestamp(),
+            level.to_lowercase(),
+            self.rng.pick(&SERVICES),
+            msg,
+            self.rng.pick(&ENDPOINTS),
+            self.rng.below(2000)
+        )
+    }
+
+    fn plain_line(&mut self, level: &str, color: &str) -> String {
+        let level = if self.rng.chance(40) {
+            format!("{}{:<5}\x1b[0m", color, level)
+        } else {
+            format!("{:<5}", level)
+        };
+        let service = self.rng.pick(&SERVICES);
+        let worker = self.rng.below(8);
+        let msg = self.message();
+        format!(
+            "{} {} [{}-{}] {}",
+            self.timestamp(),
+            level,
+            service,
+            worker,
+            msg
+        )
+    }
+
+    fn stack_trace(&mut self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{} ERROR [worker-{}] thread 'main' panicked: called `Option::unwrap()` on a `None` value",
+            self.timestamp(),
+            self.rng.below(8)
+        )];
+        let depth = 2 + self.rng.below(FRAMES.len() as u64 - 1) as usize;
+        lines.extend(FRAMES[..depth].iter().map(|frame| format!("    {}", frame)));
+        lines
+    }
+}
+
+impl Iterator for Generator {
+    /// Delay before the entry and the lines it consists of.
+    type Item = (Duration, Vec<String>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self
+            .config
+            .count
+            .is_some_and(|count| self.produced >= count)
+        {
+            return None;
+        }
+
+        let mut delay = self.delay();
+        if delay >= self.phase_left {
+            delay = self.phase_left;
+            self.next_phase();
+        } else {
+            self.phase_left -= delay;
+        }
+        self.clock += delay;
+
+        let (level, color) = self.level();
+        let lines = if level == "ERROR" && self.rng.chance(30) {
+            self.stack_trace()
+        } else if self.rng.chance(40) {
+            vec![self.json_line(level)]
+        } else if self.rng.chance(30) {
+            vec![self.logfmt_line(level)]
+        } else {
+            vec![self.plain_line(level, color)]
+        };
+        self.produced += 1;
+
+        Some((delay, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate(seed: u64) -> Vec<(Duration, Vec<String>)> {
+        Generator::new(GeneratorConfig {
+            rate: 10.0,
+            seed,
+            count: Some(200),
+            bursts: true,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_lines() {
+        let lines = generate(42);
+        assert_eq!(lines.len(), 200);
+        assert_eq!(lines, generate(42));
+    }
+
+    #[test]
+    fn different_seeds_give_different_lines() {
+        assert_ne!(generate(42), generate(43));
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::mpsc::{SendError, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::Event;
+use crate::internal::domain::record::Record;
+use crate::internal::services::capture::offsets;
+
+const MIN_SPEED: f64 = 1.0 / 16.0;
+const MAX_SPEED: f64 = 64.0;
+/// How often the replay clock is refreshed while waiting for the next record.
+const TICK: Duration = Duration::from_millis(250);
+
+struct State {
+    paused: bool,
+    speed: f64,
+    position: Duration,
+    seek: Option<Duration>,
+    steps: usize,
+    finished: bool,
+}
+
+pub struct ReplayStatus {
+    pub paused: bool,
+    pub finished: bool,
+    pub speed: f64,
+    /// Original wall-clock time of the current replay position.
+    pub clock: SystemTime,
+}
+
+/// Controls a running [`Replay`] from another thread.
+#[derive(Clone)]
+pub struct ReplayHandle {
+    shared: Arc<(Mutex<State>, Condvar)>,
+    start: SystemTime,
+}
+
+impl ReplayHandle {
+    pub fn toggle_pause(&self) {
+        self.update(|s| s.paused = !s.paused);
+    }
+    /// Pauses the replay and emits the next record.
+    pub fn step(&self) {
+        self.update(|s| {
+            s.paused = true;
+            s.steps += 1;
+        });
+    }
+    pub fn faster(&self) {
+        self.update(|s| s.speed = (s.speed * 2.0).min(MAX_SPEED));
+    }
+    pub fn slower(&self) {
+        self.update(|s| s.speed = (s.speed / 2.0).max(MIN_SPEED));
+    }
+    pub fn seek_forward(&self, by: Duration) {
+        self.update(|s| s.seek = Some(s.seek.unwrap_or(s.position).saturating_add(by)));
+    }
+    pub fn seek_backward(&self, by: Duration) {
+        self.update(|s| s.seek = Some(s.seek.unwrap_or(s.position).saturating_sub(by)));
+    }
+    pub fn status(&self) -> ReplayStatus {
+        let state = self.shared.0.lock().unwrap();
+        ReplayStatus {
+            paused: state.paused,
+            finished: state.finished,
+            speed: state.speed,
+            clock: self.start + state.position,
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut State)) {
+        let (lock, cvar) = &*self.shared;
+        f(&mut lock.lock().unwrap());
+        cvar.notify_all();
+    }
+}
+
+enum Action {
+    Seek(Duration),
+    Step,
+    Play,
+}
+
+/// Re-emits captured records keeping their original timing.
+pub struct Replay {
+    records: Vec<Record>,
+    offsets: Vec<Duration>,
+    handle: ReplayHandle,
+}
+
+impl Replay {
+    pub fn new(records: Vec<Record>, speed: f64, paused: bool) -> Self {
+        let start = records
+            .first()
+            .map(|r| r.arrived_at())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let state = State {
+            paused,
+            speed: speed.clamp(MIN_SPEED, MAX_SPEED),
+            position: Duration::ZERO,
+            seek: None,
+            steps: 0,
+            finished: records.is_empty(),
+        };
+        Self {
+            offsets: offsets(&records),
+            records,
+            handle: ReplayHandle {
+                shared: Arc::new((Mutex::new(state), Condvar::new())),
+                start,
+            },
+        }
+    }
+
+    pub fn handle(&self) -> ReplayHandle {
+        self.handle.clone()
+    }
+
+    pub fn run(self, tx: Sender<Event>) -> Result<(), SendError<Event>> {
+        let (lock, cvar) = &*self.handle.shared;
+        let duration = self.offsets.last().copied().unwrap_or_default();
+        let mut next = 0;
+        let mut position = Duration::ZERO;
+
+        loop {
+            let action = {
+                let mut state = lock.lock().unwrap();
+                state.position = position;
+                state.finished = next == self.records.len();
+
+                if let Some(target) = state.seek.take() {
+                    Action::Seek(target.min(duration))
+                } else if state.steps > 0 {
+                    state.steps -= 1;
+                    Action::Step
+                } else if state.paused || state.finished {
+                    drop(cvar.wait(state).unwrap());
+                    continue;
+                } else {
+                    let speed = state.speed;
+                    let due = self.offsets[next].saturating_sub(position).div_f64(speed);
+                    let started = Instant::now();
+                    drop(cvar.wait_timeout(state, due.min(TICK)).unwrap());
+                    position =
+                        (position + started.elapsed().mul_f64(speed)).min(self.offsets[next]);
+                    Action::Play
+                }
+            };
+
+            match action {
+                Action::Seek(target) => {
+                    if target < position {
+                        tx.send(Event::Clear)?;
+                        next = 0;
+                    }
+                    position = target;
+                }
+                Action::Step => {
+                    if let Some(offset) = self.offsets.get(next) {
+                        position = *offset;
+                    }
+                }
+                Action::Play => {}
+            }
+
+            while next < self.records.len() && self.offsets[next] <= position {
+                tx.send(Event::StdIn(self.records[next].clone()))?;
+                next += 1;
+            }
+            tx.send(Event::Tick)?;
+        }
+    }
+}
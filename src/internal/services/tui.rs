@@ -1,19 +1,60 @@
+pub mod bookmarks;
+pub mod breakdown;
+pub mod detail;
 pub mod help;
 pub mod key_inputs;
+pub mod keymap;
 pub mod logs;
+pub mod panes;
+pub mod patterns;
+pub mod rate;
+pub mod render;
 pub mod search_input;
 pub mod stats;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+pub mod table;
+pub mod tabs;
+pub mod theme;
+use std::time::{Duration, Instant, SystemTime};
+
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent, KeyModifiers, MouseButton,
+    MouseEvent, MouseEventKind,
+};
 
 use crate::{
     Event,
     internal::{
-        domain::records::RecordsError,
-        services::tui::{help::Help, logs::Logs, stats::Stats},
+        domain::{
+            extractor::Extractor, level::Level, parser::Rule, record::TimeSource,
+            records::RecordsError, redact::Redactor,
+        },
+        services::{
+            clipboard::{Clipboard, Copied},
+            replay::ReplayHandle,
+            tui::{
+                bookmarks::{BookmarkList, BookmarksOverlay},
+                breakdown::Breakdown,
+                detail::{Detail, DetailSplit},
+                help::Help,
+                keymap::{Action, Keymap},
+                logs::{Logs, ScrollbarHit},
+                panes::{Pane, Panes},
+                patterns::{PatternList, PatternsOverlay},
+                rate::{RATE_HEIGHT, RatePanel, RateView},
+                render::Highlight,
+                render::LevelColoring,
+                stats::Stats,
+                table::ColumnChooser,
+                tabs::{Tab, Tabs},
+                theme::Theme,
+            },
+        },
     },
 };
 
-use ratatui::layout::{Position, Rect};
+use ratatui::layout::{Direction, Position, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders};
 use search_input::SearchInput;
 
 #[derive(thiserror::Error, Debug)]
@@ -26,6 +67,18 @@ pub enum TuiError {
     Channel(#[from] std::sync::mpsc::RecvError),
 }
 
+/// How far `Alt+Left/Right` moves the replay clock.
+const REPLAY_SEEK_STEP: Duration = Duration::from_secs(10);
+
+/// Lines the help scrolls by on page up/down.
+const HELP_PAGE: u16 = 10;
+
+/// Lines scrolled by a turn of the mouse wheel.
+const WHEEL_STEP: usize = 3;
+
+/// How long a message stays in the stats.
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct App {
     exit: bool,
     logs: Logs,
@@ -35,6 +88,38 @@ pub struct App {
     show_help: bool,
     show_scrollbar: bool,
     show_input: bool,
+    show_columns: bool,
+    breakdown: Breakdown,
+    show_breakdown: bool,
+    rate_view: RateView,
+    silence_alert: Option<Duration>,
+    show_detail: bool,
+    detail_split: DetailSplit,
+    detail_strip_ansi: bool,
+    detail_scroll: u16,
+    patterns: PatternList,
+    show_patterns: bool,
+    bookmarks: BookmarkList,
+    show_bookmarks: bool,
+    clipboard: Clipboard,
+    message: Option<(String, Instant)>,
+    keymap: Keymap,
+    theme: Theme,
+    panes: Panes,
+    /// Where the panes went on the last draw, for mouse clicks.
+    pane_areas: Vec<(usize, Rect)>,
+    tabs: Tabs,
+    /// New name of the active tab while it is being renamed.
+    renaming: Option<SearchInput>,
+    /// Where the tabs went in the tab bar on the last draw, for mouse clicks.
+    tab_areas: Vec<(usize, Rect)>,
+    mouse_capture: bool,
+    /// Scrollbar whose thumb is being dragged.
+    dragging: Option<ScrollbarHit>,
+    /// Where the search input and the detail pane went on the last draw, for mouse clicks.
+    input_area: Rect,
+    detail_area: Option<Rect>,
+    replay: Option<ReplayHandle>,
     rx: std::sync::mpsc::Receiver<Event>,
 }
 
@@ -49,10 +134,109 @@ impl App {
             show_help: false,
             show_scrollbar: true,
             show_input: true,
+            show_columns: false,
+            breakdown: Breakdown::default(),
+            show_breakdown: false,
+            rate_view: RateView::Off,
+            silence_alert: None,
+            show_detail: false,
+            detail_split: DetailSplit::default(),
+            detail_strip_ansi: false,
+            detail_scroll: 0,
+            patterns: PatternList::default(),
+            show_patterns: false,
+            bookmarks: BookmarkList::default(),
+            show_bookmarks: false,
+            clipboard: Clipboard::default(),
+            message: None,
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            panes: Panes::default(),
+            pane_areas: vec![],
+            tabs: Tabs::default(),
+            renaming: None,
+            tab_areas: vec![],
+            mouse_capture: false,
+            dragging: None,
+            input_area: Rect::default(),
+            detail_area: None,
+            replay: None,
             rx,
         }
     }
 
+    pub fn set_replay(&mut self, replay: ReplayHandle) {
+        self.replay = Some(replay);
+    }
+
+    pub fn set_level_coloring(&mut self, coloring: LevelColoring) {
+        self.logs.set_level_coloring(coloring);
+    }
+
+    pub fn set_rules(&mut self, rules: Vec<Rule>) {
+        self.logs.records_mut().set_rules(rules);
+    }
+
+    pub fn set_captures(&mut self, captures: Vec<Extractor>) {
+        self.breakdown.set_captures(captures);
+    }
+
+    /// Separators between records further apart than `threshold`
+    /// and a warning about sources silent for `silence_alert`.
+    pub fn set_gaps(
+        &mut self,
+        threshold: Option<Duration>,
+        time: TimeSource,
+        silence_alert: Option<Duration>,
+    ) {
+        self.logs.set_gaps(threshold, time);
+        self.silence_alert = silence_alert;
+    }
+
+    pub fn set_highlights(&mut self, highlights: Vec<Highlight>) {
+        self.logs.set_highlights(highlights);
+    }
+
+    pub fn set_redactor(&mut self, redactor: Redactor) {
+        self.logs.set_redactor(redactor);
+    }
+
+    pub fn set_clipboard(&mut self, clipboard: Clipboard) {
+        self.clipboard = clipboard;
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.help = Help::new(&keymap);
+        self.help.set_theme(self.theme.clone());
+        self.breakdown.set_focus_key(
+            keymap
+                .keys(Action::Breakdown)
+                .next()
+                .map(ToString::to_string),
+        );
+        self.keymap = keymap;
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.logs.set_theme(theme.clone());
+        self.input.set_theme(theme.clone());
+        self.stats.set_theme(theme.clone());
+        self.help.set_theme(theme.clone());
+        self.breakdown.set_theme(theme.clone());
+        self.theme = theme;
+    }
+
+    /// Turns mouse capture on or off, off leaves selecting text to the terminal.
+    pub fn set_mouse_capture(&mut self, on: bool) -> Result<(), TuiError> {
+        if on {
+            crossterm::execute!(std::io::stdout(), EnableMouseCapture)?;
+        } else {
+            crossterm::execute!(std::io::stdout(), DisableMouseCapture)?;
+        }
+        self.mouse_capture = on;
+        Ok(())
+    }
+
     pub fn run(&mut self, terminal: &mut ratatui::DefaultTerminal) -> Result<(), TuiError> {
         // draw before any event happen
         terminal.draw(|frame| self.draw(frame))?;
@@ -61,10 +245,40 @@ impl App {
             match self.rx.recv()? {
                 Event::StdIn(i) => {
                     self.logs.records_mut().add(i);
+                    let index = self.logs.records().len() - 1;
+                    self.tabs
+                        .record_added(self.logs.records(), index, &self.theme);
                 }
                 Event::KeyInput(event) => {
                     self.handle_key_input(event);
                 }
+                Event::Mouse(event) => {
+                    self.handle_mouse_input(event);
+                }
+                Event::Paste(text) => match (&mut self.renaming, self.breakdown.prompt_mut()) {
+                    (Some(name), _) => name.paste(&text),
+                    (None, Some(prompt)) => prompt.paste(&text),
+                    (None, None) => self.edit_search(|input| input.paste(&text)),
+                },
+                Event::Clear => {
+                    self.logs.records_mut().clear();
+                    self.logs.collapse_duplicates();
+                    self.panes.forget_records();
+                    self.tabs.forget_records();
+                }
+                Event::Resize(..) => {
+                    // the layout, wrapping and scroll follow on the draw below
+                    terminal.autoresize()?;
+                }
+                Event::Focus(focused) => {
+                    // the button may have been released outside of the window
+                    self.dragging = None;
+                    if focused {
+                        // some terminals lose the screen while in the background
+                        terminal.clear()?;
+                    }
+                }
+                Event::Tick => {}
             }
             terminal.draw(|frame| self.draw(frame))?;
         }
@@ -72,27 +286,20 @@ impl App {
     }
 
     fn draw(&mut self, frame: &mut ratatui::Frame) {
+        self.draw_widgets(frame);
+        self.theme.finish(frame.buffer_mut());
+    }
+
+    fn draw_widgets(&mut self, frame: &mut ratatui::Frame) {
         // Render help
         if self.show_help {
-            let help_v_layout = ratatui::layout::Layout::vertical([
-                ratatui::layout::Constraint::Percentage(25),
-                ratatui::layout::Constraint::Min(3),
-                ratatui::layout::Constraint::Percentage(25),
-            ])
-            .split(frame.area())[1];
-            let help_area = ratatui::layout::Layout::horizontal([
-                ratatui::layout::Constraint::Percentage(30),
-                ratatui::layout::Constraint::Min(3),
-                ratatui::layout::Constraint::Percentage(30),
-            ])
-            .split(help_v_layout)[1];
-            frame.render_widget(&mut self.help, help_area);
+            frame.render_widget(&mut self.help, frame.area());
             return;
         }
 
         //Build layout
         let mut search_area = Rect::new(0, 0, 0, 0);
-        let mut logs_area = Rect::new(0, 0, 0, 0);
+        let mut logs_area;
         if self.show_input {
             let vertical_layout = ratatui::layout::Layout::vertical([
                 ratatui::layout::Constraint::Min(3),
@@ -105,96 +312,804 @@ impl App {
             [logs_area] = vertical_layout.areas(frame.area());
         }
 
+        self.stats
+            .set_replay(self.replay.as_ref().map(|replay| replay.status()));
+        self.stats.set_revealed(self.logs.reveal_secrets());
+        self.message = self
+            .message
+            .take()
+            .filter(|(_, shown)| shown.elapsed() < MESSAGE_TIMEOUT);
+        self.stats
+            .set_message(self.message.as_ref().map(|(message, _)| message.clone()));
+        let now = match &self.replay {
+            Some(replay) => replay.status().clock,
+            None => SystemTime::now(),
+        };
+        let mut silent: Vec<_> = self
+            .logs
+            .records()
+            .last_arrivals()
+            .iter()
+            .filter_map(|(source, last)| {
+                let silence = now.duration_since(*last).ok()?;
+                let alert = self.silence_alert?;
+                (silence >= alert).then(|| (source.clone(), silence))
+            })
+            .collect();
+        silent.sort();
+        self.stats.set_silent(silent);
+
         let mut input_area = Rect::new(0, 0, 0, 0);
         let mut stats_area = Rect::new(0, 0, 0, 0);
         if self.show_input {
             let search_layout = ratatui::layout::Layout::horizontal([
                 ratatui::layout::Constraint::Percentage(100),
-                ratatui::layout::Constraint::Min(14),
+                ratatui::layout::Constraint::Min(self.stats.width()),
             ]);
             [input_area, stats_area] = search_layout.areas(search_area);
         }
 
-        // Render logs
+        // Render the tab bar over the logs once there are several tabs
+        self.tab_areas.clear();
+        let mut prompt_cursor = None;
+        if self.tabs.is_tabbed() || self.renaming.is_some() {
+            let tab_bar_area;
+            [tab_bar_area, logs_area] = ratatui::layout::Layout::vertical([
+                ratatui::layout::Constraint::Length(1),
+                ratatui::layout::Constraint::Min(0),
+            ])
+            .areas(logs_area);
+            prompt_cursor = self.draw_tab_bar(frame, tab_bar_area);
+        }
+
+        // Render rate panel under the logs
+        if self.rate_view != RateView::Off {
+            let rate_area;
+            [logs_area, rate_area] = ratatui::layout::Layout::vertical([
+                ratatui::layout::Constraint::Min(0),
+                ratatui::layout::Constraint::Length(RATE_HEIGHT),
+            ])
+            .areas(logs_area);
+            let panel = RatePanel {
+                view: self.rate_view,
+                records: self.logs.records(),
+                now,
+                theme: &self.theme,
+            };
+            frame.render_widget(panel, rate_area);
+        }
+
+        // Render breakdown panel next to the logs
+        if self.show_breakdown {
+            let breakdown_area;
+            [logs_area, breakdown_area] = ratatui::layout::Layout::horizontal([
+                ratatui::layout::Constraint::Percentage(70),
+                ratatui::layout::Constraint::Min(30),
+            ])
+            .areas(logs_area);
+            self.breakdown
+                .update(self.logs.records(), breakdown_area, self.logs.redactor());
+            frame.render_widget(&self.breakdown, breakdown_area);
+            if let Some((x, y)) = self.breakdown.prompt_cursor(breakdown_area) {
+                prompt_cursor = Some(Position::new(x, y));
+            }
+        }
+
+        // Split the logs for the detail pane
+        let mut detail_area = None;
+        if self.show_detail && self.logs.cursor().is_some() {
+            let constraints = [
+                ratatui::layout::Constraint::Percentage(60),
+                ratatui::layout::Constraint::Percentage(40),
+            ];
+            let [logs, detail] = match self.detail_split {
+                DetailSplit::Bottom => ratatui::layout::Layout::vertical(constraints),
+                DetailSplit::Side => ratatui::layout::Layout::horizontal(constraints),
+            }
+            .areas(logs_area);
+            logs_area = logs;
+            detail_area = Some(detail);
+        }
+
+        // Render panes, a title line on each once there are several
         self.logs.set_show_scrollbar(self.show_scrollbar);
-        frame.render_widget(&mut self.logs, logs_area);
+        let split = self.panes.is_split();
+        let focused = self.panes.focused();
+        let theme = &self.theme;
+        let mut pane_areas = vec![];
+        for (id, area) in self.panes.areas(logs_area) {
+            pane_areas.push((id, area));
+            self.panes
+                .with_pane(id, &mut self.logs, &mut self.input, |logs, input| {
+                    let mut inner = area;
+                    if split {
+                        let style = if id == focused {
+                            theme.accent
+                        } else {
+                            theme.muted
+                        };
+                        let block = Block::new()
+                            .borders(Borders::TOP)
+                            .border_style(style)
+                            .title(pane_title(logs, input))
+                            .title_style(style);
+                        inner = block.inner(area);
+                        frame.render_widget(block, area);
+                    }
+                    frame.render_widget(logs, inner);
+                });
+        }
+        self.pane_areas = pane_areas;
+
+        // Render the record under the cursor
+        if let (Some(area), Some(record)) = (detail_area, self.logs.cursor_record()) {
+            let detail = Detail {
+                record,
+                strip_ansi: self.detail_strip_ansi,
+                redactor: self.logs.redactor(),
+                scroll: self.detail_scroll,
+                theme: &self.theme,
+            };
+            frame.render_widget(detail, area);
+        }
+        self.detail_area = detail_area;
+        self.input_area = input_area;
 
         if self.show_input {
             self.stats.set(
                 self.logs.records().len() as u32,
                 self.logs.records().len_filtered() as u32,
             );
+            self.stats.set_levels(
+                self.logs.records().level_counts(),
+                self.logs.records().min_level(),
+            );
 
             // Render input widget
-            frame.render_widget(&mut self.input, search_area);
+            let mut filters: Vec<String> = self
+                .logs
+                .records()
+                .value_filters()
+                .iter()
+                .map(|(extractor, value)| format!("{}={}", extractor.name(), value))
+                .collect();
+            if self.logs.records().only_bookmarked() {
+                filters.push("bookmarked".to_owned());
+            }
+            self.input.set_value_filters(filters);
+            frame.render_widget(&mut self.input, input_area);
             frame.set_cursor_position(Position::new(
-                input_area.x + self.input.character_index as u16 + 1,
+                input_area.x + self.input.cursor_column() + 1,
                 input_area.y + 1,
             ));
 
             // Render stats
             frame.render_widget(&mut self.stats, stats_area);
         }
+
+        // Render columns chooser over the logs
+        if self.show_columns {
+            let chooser = ColumnChooser {
+                table: self.logs.table(),
+                keys: self.logs.records().keys(),
+                theme: &self.theme,
+            };
+            frame.render_widget(chooser, overlay_area(logs_area));
+        }
+
+        // Render patterns over the logs
+        if self.show_patterns {
+            let overlay = PatternsOverlay {
+                list: &self.patterns,
+                records: self.logs.records(),
+                redactor: self.logs.redactor(),
+                theme: &self.theme,
+            };
+            frame.render_widget(overlay, logs_area);
+        }
+
+        // Render bookmarks over the logs
+        if self.show_bookmarks {
+            let overlay = BookmarksOverlay {
+                list: &self.bookmarks,
+                records: self.logs.records(),
+                redactor: self.logs.redactor(),
+                theme: &self.theme,
+            };
+            frame.render_widget(overlay, overlay_area(logs_area));
+        }
+
+        if let Some(position) = prompt_cursor {
+            frame.set_cursor_position(position);
+        }
+    }
+
+    /// Draws the tab names, unread records of the background ones and the rename prompt,
+    /// returns where the cursor goes while renaming.
+    fn draw_tab_bar(&mut self, frame: &mut ratatui::Frame, area: Rect) -> Option<Position> {
+        let mut spans = vec![];
+        let mut x = area.x;
+        let mut cursor = None;
+        for (i, tab) in self.tabs.iter().enumerate() {
+            let active = i == self.tabs.active();
+            let label = match &self.renaming {
+                Some(name) if active => {
+                    let prefix = format!(" {}:", i + 1);
+                    cursor = Some(x + prefix.len() as u16 + name.cursor_column());
+                    format!("{}{} ", prefix, name.value)
+                }
+                _ => format!(" {}:{} ", i + 1, tab.name()),
+            };
+            let style = if active {
+                self.theme.selected
+            } else {
+                self.theme.text
+            };
+            let label = Span::styled(label, style);
+            let mut width = label.width() as u16;
+            spans.push(label);
+            if !active && tab.unread() > 0 {
+                let unread = Span::styled(format!("+{} ", tab.unread()), self.theme.accent);
+                width += unread.width() as u16;
+                spans.push(unread);
+            }
+            self.tab_areas
+                .push((i, Rect::new(x, area.y, width, 1).intersection(area)));
+            x = x.saturating_add(width);
+            spans.push(Span::styled("│", self.theme.muted));
+            x = x.saturating_add(1);
+        }
+        if self.renaming.is_some() {
+            spans.push(Span::styled(
+                " rename: enter -> keep, esc -> cancel",
+                self.theme.muted,
+            ));
+        }
+        frame.render_widget(Line::from(spans), area);
+        cursor.map(|column| Position::new(column.min(area.right()), area.y))
     }
 
     fn handle_key_input(&mut self, key: KeyEvent) {
         match key {
-            _ if key.code == KeyCode::Up => {
-                self.logs.scroll_up();
+            _ if self.renaming.is_some() && self.keymap.action(&key) != Some(Action::Quit) => {
+                self.handle_rename_key_input(key);
+            }
+            // the chooser gets everything but Ctrl shortcuts
+            _ if self.show_columns && key.modifiers != KeyModifiers::CONTROL => {
+                self.handle_columns_key_input(key);
+            }
+            _ if self.show_patterns && key.modifiers != KeyModifiers::CONTROL => {
+                self.handle_patterns_key_input(key);
+            }
+            _ if self.show_bookmarks && key.modifiers == KeyModifiers::NONE => {
+                self.handle_bookmarks_key_input(key);
+            }
+            _ if self.breakdown.prompt_mut().is_some()
+                && self.keymap.action(&key) != Some(Action::Quit) =>
+            {
+                self.handle_breakdown_prompt_key_input(key);
             }
-            _ if key.code == KeyCode::Down => {
-                self.logs.scroll_down();
+            // the focused breakdown panel gets navigation keys
+            _ if self.show_breakdown
+                && self.breakdown.focused()
+                && key.modifiers == KeyModifiers::NONE
+                && matches!(
+                    key.code,
+                    KeyCode::Up
+                        | KeyCode::Down
+                        | KeyCode::Left
+                        | KeyCode::Right
+                        | KeyCode::Enter
+                        | KeyCode::Backspace
+                        | KeyCode::Esc
+                        | KeyCode::Char('/')
+                ) =>
+            {
+                self.handle_breakdown_key_input(key);
             }
-            _ if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL => {
-                self.exit = true;
+            _ => match self.keymap.action(&key) {
+                Some(action) => self.handle_action(action),
+                None => self.edit_search(|input| input.process_input(key)),
+            },
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::Up if self.show_help => self.help.scroll_up(1),
+            Action::Down if self.show_help => self.help.scroll_down(1),
+            Action::PageUp if self.show_help => self.help.scroll_up(HELP_PAGE),
+            Action::PageDown if self.show_help => self.help.scroll_down(HELP_PAGE),
+            Action::Quit => self.exit = true,
+            Action::Help => self.show_help = !self.show_help,
+            Action::Close => {
+                if self.show_help {
+                    self.show_help = false;
+                } else if self.logs.selection().is_some() {
+                    self.logs.clear_selection();
+                } else if self.show_detail {
+                    self.show_detail = false;
+                } else if self.show_breakdown {
+                    self.show_breakdown = false;
+                } else {
+                    self.edit_search(SearchInput::clear);
+                }
+            }
+            Action::ToggleScrollbar => self.show_scrollbar = !self.show_scrollbar,
+            Action::ToggleSearch => {
+                self.show_input = !self.show_input;
+                if !self.show_input {
+                    self.input.clear();
+                    self.logs.records_mut().set_filter_key("".into());
+                }
             }
-            _ if key.code == KeyCode::Char('q') && key.modifiers == KeyModifiers::CONTROL => {
-                self.exit = true;
+            Action::KillSearch => self.edit_search(SearchInput::kill_to_start),
+            Action::MouseCapture => {
+                if let Err(e) = self.set_mouse_capture(!self.mouse_capture) {
+                    self.message = Some((format!("mouse capture: {}", e), Instant::now()));
+                }
             }
-            _ if key.code == KeyCode::Char('d') && key.modifiers == KeyModifiers::CONTROL => {
-                self.logs.scroll_down();
+            Action::RenderMode => self.logs.cycle_render_mode(),
+            Action::TableView => {
+                self.logs.toggle_table_view();
+                self.show_columns = false;
             }
-            _ if key.code == KeyCode::Char('u') && key.modifiers == KeyModifiers::CONTROL => {
-                self.logs.scroll_up();
+            Action::Columns => {
+                self.show_columns = !self.show_columns && self.logs.table_view();
+                self.show_patterns = false;
             }
-            _ if key.code == KeyCode::Char('n') && key.modifiers == KeyModifiers::CONTROL => {
-                self.logs.scroll_down_many();
+            Action::Patterns => {
+                self.show_patterns = !self.show_patterns;
+                self.show_columns = false;
             }
-            _ if key.code == KeyCode::Char('p') && key.modifiers == KeyModifiers::CONTROL => {
-                self.logs.scroll_up_many();
+            Action::Dedup => self.logs.cycle_dedup(),
+            Action::ExpandDuplicates => self.logs.toggle_expand_duplicates(),
+            Action::RatePanel => self.rate_view = self.rate_view.next(),
+            // opens the panel focused, focuses it back, closes it when focused
+            Action::Breakdown => {
+                let focused = self.show_breakdown && self.breakdown.focused();
+                self.show_breakdown = !focused;
+                self.breakdown.set_focused(!focused);
             }
-            _ if key.code == KeyCode::Char('a') && key.modifiers == KeyModifiers::CONTROL => {
+            Action::MinLevel => {
+                let records = self.logs.records_mut();
+                records.set_min_level(Level::next_threshold(records.min_level()));
+                self.logs.scroll_to_top();
                 self.logs.set_auto_scroll();
             }
-            _ if key.code == KeyCode::Char('t') && key.modifiers == KeyModifiers::CONTROL => {
+            Action::RevealSecrets => self.logs.toggle_reveal_secrets(),
+            Action::Highlights => self.logs.toggle_highlights(),
+            Action::Gaps => self.logs.toggle_gaps(),
+            Action::WrapLines => self.logs.toggle_wrap_lines(),
+            Action::Up if self.logs.cursor().is_some() => {
+                self.logs.move_cursor(-1);
+                self.detail_scroll = 0;
+            }
+            Action::Down if self.logs.cursor().is_some() => {
+                self.logs.move_cursor(1);
+                self.detail_scroll = 0;
+            }
+            Action::Up | Action::ScrollUp => self.logs.scroll_up(),
+            Action::Down | Action::ScrollDown => self.logs.scroll_down(),
+            Action::PageUp => self.logs.scroll_up_many(),
+            Action::PageDown => self.logs.scroll_down_many(),
+            Action::AutoScroll => self.logs.set_auto_scroll(),
+            Action::Top => self.logs.scroll_to_top(),
+            Action::ScrollLeft => self.logs.scroll_left(),
+            Action::ScrollRight => self.logs.scroll_right(),
+            Action::NextMatch => self.logs.jump_to_match(true),
+            Action::PreviousMatch => self.logs.jump_to_match(false),
+            Action::Cursor => {
+                self.logs.toggle_cursor();
+                if self.logs.cursor().is_none() {
+                    self.show_detail = false;
+                }
+            }
+            Action::Details => {
+                if self.logs.cursor().is_some() {
+                    self.show_detail = !self.show_detail;
+                    self.detail_scroll = 0;
+                    self.logs.follow_cursor();
+                }
+            }
+            Action::DetailSplit => {
+                self.detail_split = self.detail_split.next();
+                self.logs.follow_cursor();
+            }
+            Action::DetailAnsi => self.detail_strip_ansi = !self.detail_strip_ansi,
+            Action::DetailUp => self.detail_scroll = self.detail_scroll.saturating_sub(1),
+            Action::DetailDown => self.detail_scroll = self.detail_scroll.saturating_add(1),
+            Action::Select => self.logs.toggle_selection(),
+            Action::Copy => {
+                if let Some(text) = self.logs.selected_text() {
+                    self.copy(&text);
+                    self.logs.clear_selection();
+                }
+            }
+            Action::CopyAll => {
+                let text = self.logs.filtered_text();
+                self.copy(&text);
+            }
+            Action::Bookmark => {
+                if self.logs.cursor().is_none() {
+                    self.logs.toggle_cursor();
+                }
+                if let Some(cursor) = self.logs.cursor() {
+                    self.logs.records_mut().toggle_bookmark(cursor);
+                }
+            }
+            Action::NextBookmark => self.logs.jump_to_bookmark(true),
+            Action::PreviousBookmark => self.logs.jump_to_bookmark(false),
+            Action::Bookmarks => {
+                self.show_bookmarks = !self.show_bookmarks;
+                self.bookmarks.clamp(self.logs.records());
+            }
+            Action::OnlyBookmarked => {
+                self.logs.records_mut().toggle_only_bookmarked();
                 self.logs.scroll_to_top();
+                self.logs.set_auto_scroll();
+                self.logs.follow_cursor();
             }
-            _ if key.code == KeyCode::Char('b') && key.modifiers == KeyModifiers::CONTROL => {
-                self.show_scrollbar = !self.show_scrollbar;
+            Action::SplitDown => self.split_pane(Direction::Vertical),
+            Action::SplitRight => self.split_pane(Direction::Horizontal),
+            Action::ClosePane => {
+                if self.panes.close(&mut self.logs, &mut self.input) {
+                    self.pane_changed();
+                }
             }
-            _ if key.code == KeyCode::Char('s') && key.modifiers == KeyModifiers::CONTROL => {
-                self.show_input = !self.show_input;
-                if !self.show_input {
-                    self.input.clear();
-                    self.logs.records_mut().set_filter_key("".into());
+            Action::NextPane | Action::PreviousPane => {
+                let forward = action == Action::NextPane;
+                self.panes.cycle(forward, &mut self.logs, &mut self.input);
+                self.pane_changed();
+            }
+            Action::GrowPane => self.panes.resize(true),
+            Action::ShrinkPane => self.panes.resize(false),
+            Action::SaveView => {
+                let name = match self.input.value.trim() {
+                    "" => "view".to_owned(),
+                    search => search.to_owned(),
+                };
+                let tab = Tab::new(name, self.logs.records().filter().clone(), &self.theme);
+                self.tabs
+                    .add(tab, &mut self.panes, &mut self.logs, &mut self.input);
+                self.pane_changed();
+            }
+            Action::RenameTab => {
+                let mut name = SearchInput::new();
+                name.paste(
+                    self.tabs
+                        .iter()
+                        .nth(self.tabs.active())
+                        .map_or("", Tab::name),
+                );
+                self.renaming = Some(name);
+            }
+            Action::CloseTab => {
+                if self
+                    .tabs
+                    .close(&mut self.panes, &mut self.logs, &mut self.input)
+                {
+                    self.pane_changed();
                 }
             }
-            _ if key.code == KeyCode::Char('h') && key.modifiers == KeyModifiers::CONTROL => {
-                self.show_help = !self.show_help;
+            Action::NextTab | Action::PreviousTab => {
+                let forward = action == Action::NextTab;
+                self.tabs
+                    .cycle(forward, &mut self.panes, &mut self.logs, &mut self.input);
+                self.pane_changed();
             }
-            _ if key.code == KeyCode::Esc && self.show_help => {
-                self.show_help = false;
+            Action::ReplayPause
+            | Action::ReplayStep
+            | Action::ReplayFaster
+            | Action::ReplaySlower
+            | Action::ReplayForward
+            | Action::ReplayBackward => self.handle_replay_action(action),
+        }
+    }
+
+    /// Applies `edit` to the search input and filters by the new text.
+    fn edit_search(&mut self, edit: impl FnOnce(&mut SearchInput)) {
+        if !self.show_input {
+            return;
+        }
+        let previous = self.input.value.clone();
+        edit(&mut self.input);
+        if self.input.value != previous {
+            self.logs
+                .records_mut()
+                .set_filter_key(self.input.value.clone().into());
+            self.logs.scroll_to_top();
+            self.logs.set_auto_scroll();
+        }
+    }
+
+    fn handle_mouse_input(&mut self, mouse: MouseEvent) {
+        // overlays take no mouse input
+        if self.show_help || self.show_columns || self.show_patterns || self.show_bookmarks {
+            return;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        if let Some((index, _)) = self
+            .tab_areas
+            .iter()
+            .find(|(_, area)| area.contains(position))
+            && mouse.kind == MouseEventKind::Down(MouseButton::Left)
+        {
+            self.tabs
+                .switch(*index, &mut self.panes, &mut self.logs, &mut self.input);
+            self.pane_changed();
+            return;
+        }
+        let over_detail = self.detail_area.is_some_and(|area| area.contains(position));
+
+        // the pane under the mouse takes the wheel and clicks
+        let focuses = matches!(
+            mouse.kind,
+            MouseEventKind::ScrollUp
+                | MouseEventKind::ScrollDown
+                | MouseEventKind::ScrollLeft
+                | MouseEventKind::ScrollRight
+                | MouseEventKind::Down(MouseButton::Left)
+        );
+        if let Some((id, _)) = self
+            .pane_areas
+            .iter()
+            .find(|(_, area)| area.contains(position))
+            && focuses
+            && !over_detail
+            && *id != self.panes.focused()
+        {
+            self.panes.focus(*id, &mut self.logs, &mut self.input);
+            self.pane_changed();
+        }
+        let sideways = mouse.modifiers == KeyModifiers::SHIFT;
+        match mouse.kind {
+            MouseEventKind::ScrollUp if over_detail => {
+                self.detail_scroll = self.detail_scroll.saturating_sub(WHEEL_STEP as u16);
             }
-            _ => {
-                if self.show_input {
-                    self.input.process_input(key);
-                    self.logs
-                        .records_mut()
-                        .set_filter_key(self.input.value.clone().into());
+            MouseEventKind::ScrollDown if over_detail => {
+                self.detail_scroll = self.detail_scroll.saturating_add(WHEEL_STEP as u16);
+            }
+            MouseEventKind::ScrollUp if sideways => self.logs.scroll_left(),
+            MouseEventKind::ScrollDown if sideways => self.logs.scroll_right(),
+            MouseEventKind::ScrollUp => {
+                for _ in 0..WHEEL_STEP {
+                    self.logs.scroll_up();
+                }
+            }
+            MouseEventKind::ScrollDown => {
+                for _ in 0..WHEEL_STEP {
+                    self.logs.scroll_down();
+                }
+            }
+            MouseEventKind::ScrollLeft => self.logs.scroll_left(),
+            MouseEventKind::ScrollRight => self.logs.scroll_right(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(hit) = self.logs.scrollbar_at(mouse.column, mouse.row) {
+                    self.dragging = Some(hit);
+                    self.logs.drag_scrollbar(hit, mouse.column, mouse.row);
+                } else if self.logs.lines_area().contains(position) {
+                    if self.logs.click(mouse.row) {
+                        self.detail_scroll = 0;
+                    }
+                } else if self.show_input && self.input_area.contains(position) {
+                    self.input
+                        .set_cursor(mouse.column.saturating_sub(self.input_area.x + 1) as usize);
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some(hit) = self.dragging {
+                    self.logs.drag_scrollbar(hit, mouse.column, mouse.row);
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => self.dragging = None,
+            _ => {}
+        }
+    }
+
+    /// Splits the focused pane, the new one starts with the filters of its tab.
+    fn split_pane(&mut self, direction: Direction) {
+        let pane = Pane::new(self.tabs.filter().clone(), &self.theme);
+        self.panes
+            .split(direction, pane, &mut self.logs, &mut self.input);
+        self.pane_changed();
+    }
+
+    /// Drops what belonged to the pane focused before.
+    fn pane_changed(&mut self) {
+        self.dragging = None;
+        self.detail_scroll = 0;
+    }
+
+    /// Copies `text` and tells how it went in the stats.
+    fn copy(&mut self, text: &str) {
+        let lines = match text.lines().count() {
+            1 => "1 line".to_owned(),
+            count => format!("{} lines", count),
+        };
+        let message = match self.clipboard.copy(text) {
+            Ok(Copied {
+                file: Some(path), ..
+            }) => format!("copied {} to {}", lines, path.display()),
+            Ok(_) => format!("copied {}", lines),
+            Err(e) => format!("copy failed: {}", e),
+        };
+        self.message = Some((message, Instant::now()));
+    }
+
+    fn handle_rename_key_input(&mut self, key: KeyEvent) {
+        let Some(name) = &mut self.renaming else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let name = name.value.trim().to_owned();
+                if !name.is_empty() {
+                    self.tabs.rename(name);
+                }
+                self.renaming = None;
+            }
+            KeyCode::Esc => self.renaming = None,
+            _ => name.process_input(key),
+        }
+    }
+
+    fn handle_columns_key_input(&mut self, key: KeyEvent) {
+        let keys = self.logs.records().keys().to_vec();
+        let table = self.logs.table_mut();
+        match key.code {
+            KeyCode::Up if key.modifiers == KeyModifiers::SHIFT => table.move_column(&keys, -1),
+            KeyCode::Down if key.modifiers == KeyModifiers::SHIFT => table.move_column(&keys, 1),
+            KeyCode::Up => table.cursor_up(),
+            KeyCode::Down => table.cursor_down(&keys),
+            KeyCode::Char(' ') | KeyCode::Enter => table.toggle_column(&keys),
+            KeyCode::Char('r') => table.reset_columns(),
+            KeyCode::Esc => self.show_columns = false,
+            _ => {}
+        }
+    }
+
+    fn handle_patterns_key_input(&mut self, key: KeyEvent) {
+        let current = self.patterns.current(self.logs.records());
+        let records = self.logs.records_mut();
+        match key.code {
+            KeyCode::Up => self.patterns.cursor_up(),
+            KeyCode::Down => self.patterns.cursor_down(records),
+            KeyCode::Char('s') => self.patterns.toggle_sort(),
+            KeyCode::Enter => {
+                if let Some(id) = current {
+                    records.toggle_shown_pattern(id);
+                }
+            }
+            KeyCode::Char('x') => {
+                if let Some(id) = current {
+                    records.toggle_hidden_pattern(id);
+                }
+            }
+            KeyCode::Char('r') => records.clear_pattern_filters(),
+            KeyCode::Esc => self.show_patterns = false,
+            _ => {}
+        }
+        if matches!(key.code, KeyCode::Enter | KeyCode::Char('x' | 'r')) {
+            self.logs.scroll_to_top();
+            self.logs.set_auto_scroll();
+        }
+    }
+
+    fn handle_bookmarks_key_input(&mut self, key: KeyEvent) {
+        let current = self.bookmarks.current(self.logs.records());
+        match key.code {
+            KeyCode::Up => self.bookmarks.cursor_up(),
+            KeyCode::Down => self.bookmarks.cursor_down(self.logs.records()),
+            KeyCode::Enter => match current {
+                Some(index) if self.logs.records().is_shown(index) => {
+                    self.logs.jump_to(Some(index));
+                    self.show_bookmarks = false;
+                }
+                Some(_) => {
+                    self.message = Some(("bookmark hidden by filters".to_owned(), Instant::now()));
+                }
+                None => {}
+            },
+            KeyCode::Char('d') => {
+                if let Some(index) = current {
+                    self.logs.records_mut().toggle_bookmark(index);
+                    self.bookmarks.clamp(self.logs.records());
+                }
+            }
+            KeyCode::Esc => self.show_bookmarks = false,
+            _ => {}
+        }
+    }
+
+    fn handle_breakdown_key_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up => self.breakdown.cursor_up(),
+            KeyCode::Down => self.breakdown.cursor_down(),
+            KeyCode::Left => self.breakdown.select(self.logs.records(), -1),
+            KeyCode::Right => self.breakdown.select(self.logs.records(), 1),
+            KeyCode::Enter => {
+                if let Some((extractor, value)) = self.breakdown.current(self.logs.records()) {
+                    self.logs.records_mut().add_value_filter(extractor, value);
                     self.logs.scroll_to_top();
                     self.logs.set_auto_scroll();
                 }
             }
+            KeyCode::Backspace => {
+                self.logs.records_mut().remove_value_filter();
+                self.logs.scroll_to_top();
+                self.logs.set_auto_scroll();
+            }
+            KeyCode::Char('/') => self.breakdown.start_prompt(),
+            KeyCode::Esc => self.breakdown.set_focused(false),
+            _ => {}
+        }
+    }
+
+    fn handle_breakdown_prompt_key_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                if let Err(e) = self.breakdown.finish_prompt() {
+                    self.message = Some((format!("bad regex: {}", e), Instant::now()));
+                }
+            }
+            KeyCode::Esc => self.breakdown.cancel_prompt(),
+            _ => {
+                if let Some(prompt) = self.breakdown.prompt_mut() {
+                    prompt.process_input(key);
+                }
+            }
         }
     }
+
+    fn handle_replay_action(&mut self, action: Action) {
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        match action {
+            Action::ReplayPause => replay.toggle_pause(),
+            Action::ReplayStep => replay.step(),
+            Action::ReplayFaster => replay.faster(),
+            Action::ReplaySlower => replay.slower(),
+            Action::ReplayForward => replay.seek_forward(REPLAY_SEEK_STEP),
+            Action::ReplayBackward => replay.seek_backward(REPLAY_SEEK_STEP),
+            _ => {}
+        }
+    }
+}
+
+/// Area in the middle of `area`, used by overlays.
+fn overlay_area(area: Rect) -> Rect {
+    let v_layout = ratatui::layout::Layout::vertical([
+        ratatui::layout::Constraint::Percentage(25),
+        ratatui::layout::Constraint::Min(3),
+        ratatui::layout::Constraint::Percentage(25),
+    ])
+    .split(area)[1];
+    ratatui::layout::Layout::horizontal([
+        ratatui::layout::Constraint::Percentage(30),
+        ratatui::layout::Constraint::Min(3),
+        ratatui::layout::Constraint::Percentage(30),
+    ])
+    .split(v_layout)[1]
+}
+
+/// `error · ≥warn · 42` on top of a pane: its search, minimum level and shown records.
+fn pane_title(logs: &Logs, input: &SearchInput) -> String {
+    let records = logs.records();
+    let mut parts = vec![];
+    if !input.value.is_empty() {
+        parts.push(input.value.clone());
+    }
+    if let Some(level) = records.min_level() {
+        parts.push(format!("≥{}", level));
+    }
+    for (extractor, value) in records.value_filters() {
+        parts.push(format!("{}={}", extractor.name(), value));
+    }
+    if parts.is_empty() {
+        parts.push("all".to_owned());
+    }
+    parts.push(records.len_filtered().to_string());
+    format!(" {} ", parts.join(" · "))
 }
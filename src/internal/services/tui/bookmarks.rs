@@ -0,0 +1,110 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
+};
+
+use crate::internal::common::strip_ansi;
+use crate::internal::domain::records::Records;
+use crate::internal::domain::redact::Redactor;
+use crate::internal::services::tui::theme::Theme;
+
+/// Overlay listing bookmarked records in arrival order.
+#[derive(Default)]
+pub struct BookmarkList {
+    cursor: usize,
+}
+
+impl BookmarkList {
+    pub fn cursor_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+    pub fn cursor_down(&mut self, records: &Records) {
+        self.cursor = (self.cursor + 1).min(records.bookmarks().len().saturating_sub(1));
+    }
+    /// Index of the record under the cursor.
+    pub fn current(&self, records: &Records) -> Option<usize> {
+        records.bookmarks().iter().nth(self.cursor).copied()
+    }
+    /// Keeps the cursor on the list after a bookmark is removed.
+    pub fn clamp(&mut self, records: &Records) {
+        self.cursor = self.cursor.min(records.bookmarks().len().saturating_sub(1));
+    }
+}
+
+pub struct BookmarksOverlay<'a> {
+    pub list: &'a BookmarkList,
+    pub records: &'a Records,
+    pub redactor: Option<&'a Redactor>,
+    pub theme: &'a Theme,
+}
+
+impl ratatui::widgets::Widget for BookmarksOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let title = if self.records.only_bookmarked() {
+            "Bookmarks (only bookmarked shown)"
+        } else {
+            "Bookmarks"
+        };
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(title)
+            .padding(Padding::horizontal(1))
+            .style(self.theme.panel.patch(self.theme.accent));
+        let inner = block.inner(area);
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let [hints_area, list_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Min(1)]).areas(inner);
+        Paragraph::new(vec![
+            Line::from("enter -> jump, d -> remove, esc -> close"),
+            Line::from("dimmed ones are hidden by filters"),
+        ])
+        .render(hints_area, buf);
+
+        if self.records.bookmarks().is_empty() {
+            Paragraph::new("no bookmarks yet, Alt+m on the line cursor adds one")
+                .render(list_area, buf);
+            return;
+        }
+
+        let height = list_area.height as usize;
+        let scroll = (self.list.cursor + 1).saturating_sub(height);
+        let lines: Vec<Line> = self
+            .records
+            .bookmarks()
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(height)
+            .filter_map(|(i, index)| {
+                let record = self.records.get(*index)?;
+                let time =
+                    chrono::DateTime::<chrono::Local>::from(record.arrived_at()).format("%H:%M:%S");
+                let value = record.to_string();
+                let value = match self.redactor {
+                    Some(redactor) => redactor.redact(&value).into_owned(),
+                    None => value,
+                };
+                let mut style = if i == self.list.cursor {
+                    self.theme.selected
+                } else {
+                    self.theme.text
+                };
+                if !self.records.is_shown(*index) {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+                Some(Line::from(vec![
+                    Span::styled(format!("{}  {}  ", time, record.source()), style),
+                    Span::styled(strip_ansi(value.trim_end()), style),
+                ]))
+            })
+            .collect();
+        Paragraph::new(lines).render(list_area, buf);
+    }
+}
@@ -0,0 +1,242 @@
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph},
+};
+use unicode_width::UnicodeWidthStr;
+
+use regex::Regex;
+
+use crate::internal::domain::extractor::Extractor;
+use crate::internal::domain::records::Records;
+use crate::internal::domain::redact::Redactor;
+use crate::internal::services::tui::search_input::SearchInput;
+use crate::internal::services::tui::theme::Theme;
+
+/// Lines of the panel taken by the title line, hints and borders.
+const CHROME_HEIGHT: usize = 5;
+const BAR_WIDTH: usize = 10;
+const PROMPT: &str = "regex: ";
+
+/// Side panel with the most frequent values of a field among filtered records.
+#[derive(Default)]
+pub struct Breakdown {
+    /// Regex captures from the config and typed in the panel, offered before parsed fields.
+    captures: Vec<Extractor>,
+    /// Whether the panel gets the navigation keys instead of the logs.
+    focused: bool,
+    /// Key which focuses the panel, for the hint.
+    focus_key: Option<String>,
+    /// Regex being typed in the panel.
+    prompt: Option<SearchInput>,
+    /// Name of the field or capture broken down.
+    selected: Option<String>,
+    cursor: usize,
+    values: Vec<(String, usize)>,
+    /// Values as shown, secrets masked.
+    labels: Vec<String>,
+    total: usize,
+    theme: Theme,
+}
+
+impl Breakdown {
+    pub fn set_captures(&mut self, captures: Vec<Extractor>) {
+        self.captures = captures;
+    }
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+    pub fn set_focus_key(&mut self, key: Option<String>) {
+        self.focus_key = key;
+    }
+
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if !focused {
+            self.prompt = None;
+        }
+    }
+
+    pub fn prompt_mut(&mut self) -> Option<&mut SearchInput> {
+        self.prompt.as_mut()
+    }
+    pub fn start_prompt(&mut self) {
+        self.prompt = Some(SearchInput::new());
+    }
+    pub fn cancel_prompt(&mut self) {
+        self.prompt = None;
+    }
+
+    /// Breaks down the first group (or the whole match) of the typed regex.
+    pub fn finish_prompt(&mut self) -> Result<(), regex::Error> {
+        let Some(prompt) = &self.prompt else {
+            return Ok(());
+        };
+        let pattern = prompt.value.trim().to_owned();
+        if !pattern.is_empty() {
+            let regex = Regex::new(&pattern)?;
+            let name = format!("/{}/", pattern);
+            self.captures.retain(|c| c.name() != name);
+            self.captures.push(Extractor::Capture {
+                name: name.clone(),
+                regex,
+            });
+            self.selected = Some(name);
+            self.cursor = 0;
+        }
+        self.prompt = None;
+        Ok(())
+    }
+
+    /// Where the cursor goes while typing a regex in a panel at `area`.
+    pub fn prompt_cursor(&self, area: Rect) -> Option<(u16, u16)> {
+        let prompt = self.prompt.as_ref()?;
+        let column = area.x + 2 + PROMPT.width() as u16 + prompt.cursor_column();
+        Some((column.min(area.right().saturating_sub(2)), area.y + 1))
+    }
+
+    /// Captures first, then every known field.
+    fn choices(&self, records: &Records) -> Vec<Extractor> {
+        let mut choices = self.captures.clone();
+        choices.extend(records.keys().iter().cloned().map(Extractor::Field));
+        choices
+    }
+
+    fn extractor(&self, records: &Records) -> Option<Extractor> {
+        let choices = self.choices(records);
+        match &self.selected {
+            Some(name) => choices.into_iter().find(|c| c.name() == name),
+            None => choices.into_iter().next(),
+        }
+    }
+
+    /// Breaks down the next (`1`) or previous (`-1`) field.
+    pub fn select(&mut self, records: &Records, by: isize) {
+        let choices = self.choices(records);
+        if choices.is_empty() {
+            return;
+        }
+        let current = self
+            .extractor(records)
+            .and_then(|e| choices.iter().position(|c| c.name() == e.name()))
+            .unwrap_or(0);
+        let next = (current as isize + by).rem_euclid(choices.len() as isize) as usize;
+        self.selected = Some(choices[next].name().to_owned());
+        self.cursor = 0;
+    }
+
+    pub fn cursor_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+    pub fn cursor_down(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.values.len().saturating_sub(1));
+    }
+
+    /// Field and value under the cursor, to filter by.
+    pub fn current(&self, records: &Records) -> Option<(Extractor, String)> {
+        let extractor = self.extractor(records)?;
+        let (value, _) = self.values.get(self.cursor)?;
+        Some((extractor, value.clone()))
+    }
+
+    /// Recounts values for a panel of `area`.
+    pub fn update(&mut self, records: &Records, area: Rect, redactor: Option<&Redactor>) {
+        let k = (area.height as usize).saturating_sub(CHROME_HEIGHT).max(1);
+        (self.values, self.total) = match self.extractor(records) {
+            Some(extractor) => {
+                self.selected = Some(extractor.name().to_owned());
+                records.breakdown(&extractor, k)
+            }
+            None => (vec![], 0),
+        };
+        self.cursor = self.cursor.min(self.values.len().saturating_sub(1));
+        self.labels = self
+            .values
+            .iter()
+            .map(|(value, _)| match redactor {
+                Some(redactor) => redactor.redact(value).into_owned(),
+                None => value.clone(),
+            })
+            .collect();
+    }
+}
+
+impl ratatui::widgets::Widget for &Breakdown {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let inner_width = (area.width as usize).saturating_sub(4);
+        let mut lines = match (&self.prompt, self.focused) {
+            (Some(prompt), _) => vec![
+                Line::from(format!("{}{}", PROMPT, prompt.value)),
+                Line::from("enter -> break down, esc -> cancel"),
+            ],
+            (None, true) => vec![
+                Line::from("left/right -> field, / -> regex"),
+                Line::from("enter -> filter, bksp -> unfilter"),
+            ],
+            (None, false) => vec![
+                Line::from(match &self.focus_key {
+                    Some(key) => format!("{} -> focus", key),
+                    None => "not focused".to_owned(),
+                }),
+                Line::from("esc -> close"),
+            ],
+        };
+        lines.push(Line::from(""));
+        if self.values.is_empty() {
+            lines.push(Line::from("no values"));
+        }
+        for (i, ((_, count), value)) in self.values.iter().zip(&self.labels).enumerate() {
+            let share = *count as f64 / self.total.max(1) as f64;
+            let bar = "█".repeat((share * BAR_WIDTH as f64).round() as usize);
+            let stats = format!(" {:>5.1}% {:>6}", share * 100.0, count);
+            let value = value.replace(['\n', '\r', '\t'], " ");
+            let room = inner_width.saturating_sub(BAR_WIDTH + stats.width() + 1);
+            let mut label: String = String::new();
+            for c in value.chars() {
+                if label.width() + c.to_string().width() > room {
+                    break;
+                }
+                label.push(c);
+            }
+            let padding = " ".repeat(room.saturating_sub(label.width()) + 1);
+            let style = if i == self.cursor && self.focused {
+                self.theme.selected
+            } else {
+                Style::new()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}{}", label, padding), style),
+                Span::styled(
+                    format!("{:<width$}", bar, width = BAR_WIDTH),
+                    self.theme.value,
+                ),
+                Span::raw(stats),
+            ]));
+        }
+
+        let title = match &self.selected {
+            Some(name) => format!("Top values: {}", name),
+            None => "Top values".to_owned(),
+        };
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .border_style(if self.focused {
+                self.theme.accent
+            } else {
+                self.theme.muted
+            })
+            .title(title)
+            .padding(Padding::horizontal(1));
+        Paragraph::new(lines)
+            .style(self.theme.accent)
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+
+use ansi_to_tui::IntoText as _;
+use ratatui::{
+    layout::Rect,
+    style::Modifier,
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
+};
+
+use crate::internal::common::strip_ansi;
+use crate::internal::domain::record::{Format, Record};
+use crate::internal::domain::redact::Redactor;
+use crate::internal::services::tui::render::{LevelColoring, RenderMode, render_record};
+use crate::internal::services::tui::theme::Theme;
+
+/// Where the detail pane goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DetailSplit {
+    #[default]
+    Bottom,
+    Side,
+}
+
+impl DetailSplit {
+    pub fn next(self) -> Self {
+        match self {
+            DetailSplit::Bottom => DetailSplit::Side,
+            DetailSplit::Side => DetailSplit::Bottom,
+        }
+    }
+}
+
+/// The record under the cursor in full: metadata, text, fields and pretty JSON.
+pub struct Detail<'a> {
+    pub record: &'a Record,
+    /// Show the text without ANSI colors.
+    pub strip_ansi: bool,
+    pub redactor: Option<&'a Redactor>,
+    /// Lines scrolled off the top.
+    pub scroll: u16,
+    pub theme: &'a Theme,
+}
+
+impl ratatui::widgets::Widget for Detail<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let record = match self.redactor {
+            Some(redactor) => redactor.redact_record(self.record),
+            None => Cow::Borrowed(self.record),
+        };
+        let time = |time| {
+            chrono::DateTime::<chrono::Local>::from(time)
+                .format("%Y-%m-%d %H:%M:%S%.3f")
+                .to_string()
+        };
+        let heading = self.theme.accent.add_modifier(Modifier::BOLD);
+        let meta = |key: &str, value: Span<'static>| {
+            Line::from(vec![
+                Span::styled(format!("{:<9}", key), self.theme.muted),
+                value,
+            ])
+        };
+
+        let mut lines = vec![];
+        lines.push(meta("source", Span::raw(record.source().to_owned())));
+        lines.push(meta("arrived", Span::raw(time(record.arrived_at()))));
+        if let Some(timestamp) = record.timestamp() {
+            lines.push(meta("time", Span::raw(time(timestamp))));
+        }
+        if let Some(level) = record.level() {
+            lines.push(meta(
+                "level",
+                Span::styled(level.to_string(), self.theme.level(level)),
+            ));
+        }
+        let format = match record.format() {
+            Format::Plain => "plain",
+            Format::Json => "JSON",
+            Format::Logfmt => "logfmt",
+            Format::Custom => "custom parser",
+        };
+        lines.push(meta("format", Span::raw(format)));
+
+        lines.push(Line::default());
+        lines.push(Line::styled("Text", heading));
+        let value = record.to_string();
+        if self.strip_ansi {
+            lines.extend(
+                strip_ansi(&value)
+                    .lines()
+                    .map(|line| Line::from(line.to_owned())),
+            );
+        } else {
+            let text = value
+                .into_text()
+                .unwrap_or_else(|_| Text::from(record.to_string()));
+            lines.extend(text.lines);
+        }
+
+        if !record.fields().is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::styled("Fields", heading));
+            let width = record
+                .fields()
+                .iter()
+                .map(|(key, _)| key.chars().count())
+                .max()
+                .unwrap_or(0);
+            for (key, value) in record.fields() {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:<width$}  ", key, width = width),
+                        self.theme.muted,
+                    ),
+                    Span::styled(value.clone(), self.theme.value),
+                ]));
+            }
+        }
+
+        if record.format() == Format::Json {
+            lines.push(Line::default());
+            lines.push(Line::styled("JSON", heading));
+            lines.extend(
+                render_record(
+                    &record,
+                    RenderMode::Pretty,
+                    LevelColoring::Token,
+                    self.theme,
+                )
+                .lines,
+            );
+        }
+
+        let title = if self.strip_ansi {
+            "Record (ANSI stripped)"
+        } else {
+            "Record"
+        };
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(title)
+            .padding(Padding::horizontal(1));
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .render(area, buf);
+    }
+}
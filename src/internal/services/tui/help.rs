@@ -1,20 +1,103 @@
 use ratatui::{
     prelude::*,
-    style::{Color, Style},
+    style::Style,
     text::Line,
     widgets::{Block, Borders, Padding, Paragraph},
 };
 
-pub struct Help {}
+use crate::internal::services::tui::keymap::{Action, Keymap, Section};
+use crate::internal::services::tui::theme::Theme;
+
+/// Keys that aren't in the keymap: editing the search and the mouse.
+const FIXED_KEYS: [(&str, &str); 8] = [
+    ("home/end, left/right", "move in the search input"),
+    ("ctrl+w, alt+backspace", "delete a word"),
+    ("alt+b/f", "word back/forward"),
+    ("alt+d", "delete the next word"),
+    (
+        "ctrl+k",
+        "delete to the end (ctrl+u to the start in the tab and regex prompts)",
+    ),
+    (
+        "wheel",
+        "scroll the pane under it, over the details scrolls them, with shift sideways",
+    ),
+    (
+        "click",
+        "switch tabs, focus a pane, put the line cursor on a record, the search cursor in the text",
+    ),
+    ("drag", "move a scrollbar"),
+];
+
+/// Keys of the active keymap, grouped by section.
+pub struct Help {
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+    theme: Theme,
+}
 impl Default for Help {
     fn default() -> Self {
-        Help::new()
+        Help::new(&Keymap::default())
     }
 }
 
 impl Help {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(keymap: &Keymap) -> Self {
+        let mut entries: Vec<(Option<Section>, String, &str)> = vec![];
+        for section in Section::ALL {
+            for action in Action::ALL.iter().filter(|a| a.section() == section) {
+                let keys: Vec<String> = keymap.keys(*action).map(|key| key.to_string()).collect();
+                if !keys.is_empty() {
+                    entries.push((Some(section), keys.join(", "), action.description()));
+                }
+            }
+        }
+        for (keys, description) in FIXED_KEYS {
+            entries.push((None, keys.to_owned(), description));
+        }
+
+        let width = entries
+            .iter()
+            .map(|(_, keys, _)| keys.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec![];
+        let mut current = None;
+        for (i, (section, keys, description)) in entries.into_iter().enumerate() {
+            if i == 0 || section != current {
+                if i > 0 {
+                    lines.push(Line::from(""));
+                }
+                let title = section.map_or("Search input and mouse", Section::title);
+                lines.push(Line::styled(title, Style::new().bold()));
+                current = section;
+            }
+            lines.push(Line::from(format!(
+                "{:<width$}  {}",
+                keys,
+                description,
+                width = width
+            )));
+        }
+        Self {
+            lines,
+            scroll: 0,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn scroll_up(&mut self, by: u16) {
+        self.scroll = self.scroll.saturating_sub(by);
+    }
+    pub fn scroll_down(&mut self, by: u16) {
+        self.scroll = self
+            .scroll
+            .saturating_add(by)
+            .min(self.lines.len().saturating_sub(1) as u16);
     }
 }
 
@@ -23,30 +106,17 @@ impl ratatui::widgets::Widget for &mut Help {
     where
         Self: Sized,
     {
-        let lines = vec![
-            Line::from("Use Ctr +:"),
-            Line::from(""),
-            Line::from("c/q -> quit"),
-            Line::from(""),
-            Line::from("b -> toggle scrollbar"),
-            Line::from("s -> toggle search input"),
-            Line::from("h -> toggle help"),
-            Line::from(""),
-            Line::from("u/d -> scroll up/down (turns off autoScroll)"),
-            Line::from("p/n -> page up/down (turns off autoScroll)"),
-            Line::from(""),
-            Line::from("a -> turn on autoScroll"),
-            Line::from("t -> scroll to the top"),
-        ];
         let block = Block::new()
             .borders(Borders::NONE)
-            .padding(Padding::new(7, 0, 2, 1))
-            .style(Style::default().bg(Color::Black));
+            .title("Keys (up/down -> scroll, esc -> close)")
+            .padding(Padding::new(7, 0, 1, 1))
+            .style(self.theme.panel);
 
-        Paragraph::new(lines)
-            .style(Style::default().fg(Color::Yellow))
+        Paragraph::new(self.lines.clone())
+            .style(self.theme.accent)
             .block(block)
             .alignment(Alignment::Left)
+            .scroll((self.scroll, 0))
             .render(area, buf);
     }
 }
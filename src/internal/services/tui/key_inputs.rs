@@ -1,11 +1,18 @@
 use crate::Event;
 
-#[allow(clippy::single_match)]
 pub fn handle_key_inputs(tx: std::sync::mpsc::Sender<Event>) {
     loop {
         match crossterm::event::read().unwrap() {
             crossterm::event::Event::Key(key_event) => tx.send(Event::KeyInput(key_event)).unwrap(),
-            _ => {}
+            crossterm::event::Event::Mouse(mouse_event) => {
+                tx.send(Event::Mouse(mouse_event)).unwrap()
+            }
+            crossterm::event::Event::Paste(text) => tx.send(Event::Paste(text)).unwrap(),
+            crossterm::event::Event::Resize(width, height) => {
+                tx.send(Event::Resize(width, height)).unwrap()
+            }
+            crossterm::event::Event::FocusGained => tx.send(Event::Focus(true)).unwrap(),
+            crossterm::event::Event::FocusLost => tx.send(Event::Focus(false)).unwrap(),
         }
     }
 }
@@ -0,0 +1,792 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Something a key can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    Close,
+    ToggleScrollbar,
+    ToggleSearch,
+    KillSearch,
+    MouseCapture,
+    RenderMode,
+    TableView,
+    Columns,
+    Patterns,
+    Dedup,
+    ExpandDuplicates,
+    RatePanel,
+    Breakdown,
+    MinLevel,
+    RevealSecrets,
+    Highlights,
+    Gaps,
+    WrapLines,
+    Up,
+    Down,
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+    AutoScroll,
+    Top,
+    ScrollLeft,
+    ScrollRight,
+    NextMatch,
+    PreviousMatch,
+    Cursor,
+    Details,
+    DetailSplit,
+    DetailAnsi,
+    DetailUp,
+    DetailDown,
+    Select,
+    Copy,
+    CopyAll,
+    Bookmark,
+    NextBookmark,
+    PreviousBookmark,
+    Bookmarks,
+    OnlyBookmarked,
+    SplitDown,
+    SplitRight,
+    ClosePane,
+    NextPane,
+    PreviousPane,
+    GrowPane,
+    ShrinkPane,
+    SaveView,
+    RenameTab,
+    CloseTab,
+    NextTab,
+    PreviousTab,
+    ReplayPause,
+    ReplayStep,
+    ReplayFaster,
+    ReplaySlower,
+    ReplayForward,
+    ReplayBackward,
+}
+
+/// Group of actions in the help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    General,
+    Views,
+    Scrolling,
+    Cursor,
+    Bookmarks,
+    Panes,
+    Tabs,
+    Replay,
+}
+
+impl Section {
+    pub const ALL: [Section; 8] = [
+        Section::General,
+        Section::Views,
+        Section::Scrolling,
+        Section::Cursor,
+        Section::Bookmarks,
+        Section::Panes,
+        Section::Tabs,
+        Section::Replay,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::General => "General",
+            Section::Views => "Views",
+            Section::Scrolling => "Scrolling",
+            Section::Cursor => "Line cursor",
+            Section::Bookmarks => "Bookmarks",
+            Section::Panes => "Panes",
+            Section::Tabs => "Tabs",
+            Section::Replay => "Replay",
+        }
+    }
+}
+
+impl Action {
+    pub const ALL: [Action; 64] = [
+        Action::Quit,
+        Action::Help,
+        Action::Close,
+        Action::ToggleScrollbar,
+        Action::ToggleSearch,
+        Action::KillSearch,
+        Action::MouseCapture,
+        Action::RenderMode,
+        Action::TableView,
+        Action::Columns,
+        Action::Patterns,
+        Action::Dedup,
+        Action::ExpandDuplicates,
+        Action::RatePanel,
+        Action::Breakdown,
+        Action::MinLevel,
+        Action::RevealSecrets,
+        Action::Highlights,
+        Action::Gaps,
+        Action::WrapLines,
+        Action::Up,
+        Action::Down,
+        Action::ScrollUp,
+        Action::ScrollDown,
+        Action::PageUp,
+        Action::PageDown,
+        Action::AutoScroll,
+        Action::Top,
+        Action::ScrollLeft,
+        Action::ScrollRight,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::Cursor,
+        Action::Details,
+        Action::DetailSplit,
+        Action::DetailAnsi,
+        Action::DetailUp,
+        Action::DetailDown,
+        Action::Select,
+        Action::Copy,
+        Action::CopyAll,
+        Action::Bookmark,
+        Action::NextBookmark,
+        Action::PreviousBookmark,
+        Action::Bookmarks,
+        Action::OnlyBookmarked,
+        Action::SplitDown,
+        Action::SplitRight,
+        Action::ClosePane,
+        Action::NextPane,
+        Action::PreviousPane,
+        Action::GrowPane,
+        Action::ShrinkPane,
+        Action::SaveView,
+        Action::RenameTab,
+        Action::CloseTab,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::ReplayPause,
+        Action::ReplayStep,
+        Action::ReplayFaster,
+        Action::ReplaySlower,
+        Action::ReplayForward,
+        Action::ReplayBackward,
+    ];
+
+    /// Name in the `[keys.bindings]` config.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Close => "close",
+            Action::ToggleScrollbar => "toggle-scrollbar",
+            Action::ToggleSearch => "toggle-search",
+            Action::KillSearch => "kill-search",
+            Action::MouseCapture => "mouse-capture",
+            Action::RenderMode => "render-mode",
+            Action::TableView => "table-view",
+            Action::Columns => "columns",
+            Action::Patterns => "patterns",
+            Action::Dedup => "dedup",
+            Action::ExpandDuplicates => "expand-duplicates",
+            Action::RatePanel => "rate-panel",
+            Action::Breakdown => "breakdown",
+            Action::MinLevel => "min-level",
+            Action::RevealSecrets => "reveal-secrets",
+            Action::Highlights => "highlights",
+            Action::Gaps => "gaps",
+            Action::WrapLines => "wrap-lines",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::ScrollUp => "scroll-up",
+            Action::ScrollDown => "scroll-down",
+            Action::PageUp => "page-up",
+            Action::PageDown => "page-down",
+            Action::AutoScroll => "auto-scroll",
+            Action::Top => "top",
+            Action::ScrollLeft => "scroll-left",
+            Action::ScrollRight => "scroll-right",
+            Action::NextMatch => "next-match",
+            Action::PreviousMatch => "previous-match",
+            Action::Cursor => "cursor",
+            Action::Details => "details",
+            Action::DetailSplit => "detail-split",
+            Action::DetailAnsi => "detail-ansi",
+            Action::DetailUp => "detail-up",
+            Action::DetailDown => "detail-down",
+            Action::Select => "select",
+            Action::Copy => "copy",
+            Action::CopyAll => "copy-all",
+            Action::Bookmark => "bookmark",
+            Action::NextBookmark => "next-bookmark",
+            Action::PreviousBookmark => "previous-bookmark",
+            Action::Bookmarks => "bookmarks",
+            Action::OnlyBookmarked => "only-bookmarked",
+            Action::SplitDown => "split-down",
+            Action::SplitRight => "split-right",
+            Action::ClosePane => "close-pane",
+            Action::NextPane => "next-pane",
+            Action::PreviousPane => "previous-pane",
+            Action::GrowPane => "grow-pane",
+            Action::ShrinkPane => "shrink-pane",
+            Action::SaveView => "save-view",
+            Action::RenameTab => "rename-tab",
+            Action::CloseTab => "close-tab",
+            Action::NextTab => "next-tab",
+            Action::PreviousTab => "previous-tab",
+            Action::ReplayPause => "replay-pause",
+            Action::ReplayStep => "replay-step",
+            Action::ReplayFaster => "replay-faster",
+            Action::ReplaySlower => "replay-slower",
+            Action::ReplayForward => "replay-forward",
+            Action::ReplayBackward => "replay-backward",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "toggle help",
+            Action::Close => "close details/panels, drop the selection, clear the search",
+            Action::ToggleScrollbar => "toggle scrollbar",
+            Action::ToggleSearch => "toggle search input",
+            Action::KillSearch => "delete the search text before the cursor",
+            Action::MouseCapture => "mouse capture on/off (off lets the terminal select text)",
+            Action::RenderMode => "structured logs view: compact/pretty/raw",
+            Action::TableView => "toggle table view",
+            Action::Columns => "choose table columns (space -> show/hide, shift+up/down -> move)",
+            Action::Patterns => "patterns (enter -> show only, x -> hide, s -> sort)",
+            Action::Dedup => "collapse duplicates: off/identical/identical but numbers",
+            Action::ExpandDuplicates => "expand/collapse the duplicates under the cursor",
+            Action::RatePanel => {
+                "rate panel: per second/per minute/histogram by arrival/by parsed time"
+            }
+            Action::Breakdown => {
+                "focus/close top values (left/right -> field, / -> regex, enter -> filter, esc -> logs)"
+            }
+            Action::MinLevel => "minimum level: all/debug/info/warn/error/fatal",
+            Action::RevealSecrets => "reveal/mask secrets",
+            Action::Highlights => "turn highlight rules on/off",
+            Action::Gaps => "turn gap separators on/off",
+            Action::WrapLines => "wrap/truncate long lines",
+            Action::Up => "move the cursor up, scroll without a cursor",
+            Action::Down => "move the cursor down, scroll without a cursor",
+            Action::ScrollUp => "scroll up (turns off autoScroll)",
+            Action::ScrollDown => "scroll down (turns off autoScroll)",
+            Action::PageUp => "page up (turns off autoScroll)",
+            Action::PageDown => "page down (turns off autoScroll)",
+            Action::AutoScroll => "turn on autoScroll",
+            Action::Top => "scroll to the top",
+            Action::ScrollLeft => "scroll left (table view, lines not wrapped)",
+            Action::ScrollRight => "scroll right (table view, lines not wrapped)",
+            Action::NextMatch => "jump to the next search match",
+            Action::PreviousMatch => "jump to the previous search match",
+            Action::Cursor => "line cursor on/off",
+            Action::Details => "record details",
+            Action::DetailSplit => "details at the bottom/side",
+            Action::DetailAnsi => "details with/without ANSI colors",
+            Action::DetailUp => "scroll details up",
+            Action::DetailDown => "scroll details down",
+            Action::Select => "select from the cursor (up/down -> extend)",
+            Action::Copy => "copy the selection or the record under the cursor",
+            Action::CopyAll => "copy all filtered records",
+            Action::Bookmark => "bookmark the record under the cursor",
+            Action::NextBookmark => "next bookmark",
+            Action::PreviousBookmark => "previous bookmark",
+            Action::Bookmarks => "list bookmarks (enter -> jump, d -> remove)",
+            Action::OnlyBookmarked => "show only bookmarked records",
+            Action::SplitDown => "split the pane, the new one below with its own search",
+            Action::SplitRight => "split the pane, the new one on the right with its own search",
+            Action::ClosePane => "close the pane",
+            Action::NextPane => "focus the next pane",
+            Action::PreviousPane => "focus the previous pane",
+            Action::GrowPane => "grow the pane",
+            Action::ShrinkPane => "shrink the pane",
+            Action::SaveView => "save the pane's filters as a new tab",
+            Action::RenameTab => "rename the tab",
+            Action::CloseTab => "close the tab",
+            Action::NextTab => "switch to the next tab",
+            Action::PreviousTab => "switch to the previous tab",
+            Action::ReplayPause => "pause/resume",
+            Action::ReplayStep => "step to the next record (pauses)",
+            Action::ReplayFaster => "speed up",
+            Action::ReplaySlower => "speed down",
+            Action::ReplayForward => "seek 10s forward",
+            Action::ReplayBackward => "seek 10s back",
+        }
+    }
+
+    pub fn section(self) -> Section {
+        match self {
+            Action::Quit
+            | Action::Help
+            | Action::Close
+            | Action::ToggleScrollbar
+            | Action::ToggleSearch
+            | Action::KillSearch
+            | Action::MouseCapture => Section::General,
+            Action::RenderMode
+            | Action::TableView
+            | Action::Columns
+            | Action::Patterns
+            | Action::Dedup
+            | Action::ExpandDuplicates
+            | Action::RatePanel
+            | Action::Breakdown
+            | Action::MinLevel
+            | Action::RevealSecrets
+            | Action::Highlights
+            | Action::Gaps
+            | Action::WrapLines => Section::Views,
+            Action::Up
+            | Action::Down
+            | Action::ScrollUp
+            | Action::ScrollDown
+            | Action::PageUp
+            | Action::PageDown
+            | Action::AutoScroll
+            | Action::Top
+            | Action::ScrollLeft
+            | Action::ScrollRight
+            | Action::NextMatch
+            | Action::PreviousMatch => Section::Scrolling,
+            Action::Cursor
+            | Action::Details
+            | Action::DetailSplit
+            | Action::DetailAnsi
+            | Action::DetailUp
+            | Action::DetailDown
+            | Action::Select
+            | Action::Copy
+            | Action::CopyAll => Section::Cursor,
+            Action::Bookmark
+            | Action::NextBookmark
+            | Action::PreviousBookmark
+            | Action::Bookmarks
+            | Action::OnlyBookmarked => Section::Bookmarks,
+            Action::SplitDown
+            | Action::SplitRight
+            | Action::ClosePane
+            | Action::NextPane
+            | Action::PreviousPane
+            | Action::GrowPane
+            | Action::ShrinkPane => Section::Panes,
+            Action::SaveView
+            | Action::RenameTab
+            | Action::CloseTab
+            | Action::NextTab
+            | Action::PreviousTab => Section::Tabs,
+            Action::ReplayPause
+            | Action::ReplayStep
+            | Action::ReplayFaster
+            | Action::ReplaySlower
+            | Action::ReplayForward
+            | Action::ReplayBackward => Section::Replay,
+        }
+    }
+}
+
+/// A key with its modifiers, written like `ctrl+c`, `alt+shift+up` or `enter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let mut code = code;
+        // the char already tells about shift, terminals differ in also reporting it
+        if let KeyCode::Char(c) = code
+            && modifiers.contains(KeyModifiers::SHIFT)
+        {
+            code = KeyCode::Char(c.to_ascii_uppercase());
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+
+    /// Keys the search input takes, binding them would make them impossible to type.
+    fn is_search_input_key(&self) -> bool {
+        matches!(
+            (self.code, self.modifiers),
+            (KeyCode::Char(_), KeyModifiers::NONE)
+                | (
+                    KeyCode::Left
+                        | KeyCode::Right
+                        | KeyCode::Home
+                        | KeyCode::End
+                        | KeyCode::Backspace
+                        | KeyCode::Delete,
+                    KeyModifiers::NONE,
+                )
+                | (KeyCode::Char('w' | 'k'), KeyModifiers::CONTROL)
+                | (
+                    KeyCode::Char('b' | 'f' | 'd') | KeyCode::Backspace,
+                    KeyModifiers::ALT
+                )
+        )
+    }
+}
+
+impl From<&KeyEvent> for KeyBinding {
+    fn from(key: &KeyEvent) -> Self {
+        KeyBinding::new(key.code, key.modifiers)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("unknown key `{0}`")]
+pub struct KeyBindingError(String);
+
+impl FromStr for KeyBinding {
+    type Err = KeyBindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || KeyBindingError(s.to_owned());
+        // `ctrl++` binds the plus key
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let mut mods = KeyModifiers::NONE;
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            mods |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(error()),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(error()),
+                },
+            },
+        };
+        Ok(KeyBinding::new(code, mods))
+    }
+}
+
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Starting point for the key bindings, before those from the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    #[default]
+    Default,
+    /// `Ctr+e/y` scroll, `Ctr+f/b` page
+    Vim,
+    /// `Ctr+n/p` move, `Ctr+v`/`Alt+v` page, `Alt+<`/`Alt+>` top and bottom
+    Emacs,
+}
+
+/// Keys bound to actions.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::preset(Preset::Default)
+    }
+}
+
+impl Keymap {
+    pub fn preset(preset: Preset) -> Self {
+        let mut keymap = Keymap { bindings: vec![] };
+        for (action, keys) in DEFAULT_KEYS {
+            keymap.bind_str(action, keys);
+        }
+        let overrides: &[(Action, &[&str])] = match preset {
+            Preset::Default => &[],
+            Preset::Vim => VIM_KEYS,
+            Preset::Emacs => EMACS_KEYS,
+        };
+        for (action, keys) in overrides {
+            keymap.bind_str(*action, keys);
+        }
+        keymap
+    }
+
+    fn bind_str(&mut self, action: Action, keys: &[&str]) {
+        let keys = keys
+            .iter()
+            .map(|key| key.parse().expect("valid built-in key"))
+            .collect();
+        self.bind(action, keys);
+    }
+
+    /// Binds `keys` to `action` instead of the keys it had.
+    pub fn bind(&mut self, action: Action, keys: Vec<KeyBinding>) {
+        self.bindings.retain(|(_, bound)| *bound != action);
+        self.bindings
+            .extend(keys.into_iter().map(|key| (key, action)));
+    }
+
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        let key = KeyBinding::from(key);
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, action)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = &KeyBinding> {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| *bound == action)
+            .map(|(key, _)| key)
+    }
+
+    /// Keys bound to several actions or taken by the search input.
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = vec![];
+        for (i, (key, action)) in self.bindings.iter().enumerate() {
+            if key.is_search_input_key() {
+                conflicts.push(format!(
+                    "`{}` of `{}` is taken by the search input",
+                    key,
+                    action.name()
+                ));
+            }
+            if let Some((_, other)) = self.bindings[..i]
+                .iter()
+                .find(|(bound, other)| bound == key && other != action)
+            {
+                conflicts.push(format!(
+                    "`{}` is bound to both `{}` and `{}`",
+                    key,
+                    other.name(),
+                    action.name()
+                ));
+            }
+        }
+        conflicts
+    }
+}
+
+const DEFAULT_KEYS: [(Action, &[&str]); 64] = [
+    (Action::Quit, &["ctrl+c", "ctrl+q"]),
+    (Action::Help, &["ctrl+h"]),
+    (Action::Close, &["esc"]),
+    (Action::ToggleScrollbar, &["ctrl+b"]),
+    (Action::ToggleSearch, &["ctrl+s"]),
+    (Action::KillSearch, &["alt+u"]),
+    (Action::MouseCapture, &["alt+x"]),
+    (Action::RenderMode, &["ctrl+r"]),
+    (Action::TableView, &["ctrl+g"]),
+    (Action::Columns, &["ctrl+o"]),
+    (Action::Patterns, &["ctrl+z"]),
+    (Action::Dedup, &["ctrl+x"]),
+    (Action::ExpandDuplicates, &["ctrl+y"]),
+    (Action::RatePanel, &["ctrl+e"]),
+    (Action::Breakdown, &["ctrl+f"]),
+    (Action::MinLevel, &["ctrl+l"]),
+    (Action::RevealSecrets, &["alt+r"]),
+    (Action::Highlights, &["alt+h"]),
+    (Action::Gaps, &["alt+g"]),
+    (Action::WrapLines, &["alt+t"]),
+    (Action::Up, &["up"]),
+    (Action::Down, &["down"]),
+    (Action::ScrollUp, &["ctrl+u"]),
+    (Action::ScrollDown, &["ctrl+d"]),
+    (Action::PageUp, &["ctrl+p", "pageup"]),
+    (Action::PageDown, &["ctrl+n", "pagedown"]),
+    (Action::AutoScroll, &["ctrl+a"]),
+    (Action::Top, &["ctrl+t"]),
+    (Action::ScrollLeft, &["shift+left"]),
+    (Action::ScrollRight, &["shift+right"]),
+    (Action::NextMatch, &["alt+."]),
+    (Action::PreviousMatch, &["alt+,"]),
+    (Action::Cursor, &["alt+c"]),
+    (Action::Details, &["enter"]),
+    (Action::DetailSplit, &["alt+s"]),
+    (Action::DetailAnsi, &["alt+a"]),
+    (Action::DetailUp, &["shift+up"]),
+    (Action::DetailDown, &["shift+down"]),
+    (Action::Select, &["alt+v"]),
+    (Action::Copy, &["alt+y"]),
+    (Action::CopyAll, &["alt+w"]),
+    (Action::Bookmark, &["alt+m"]),
+    (Action::NextBookmark, &["alt+j"]),
+    (Action::PreviousBookmark, &["alt+k"]),
+    (Action::Bookmarks, &["alt+l"]),
+    (Action::OnlyBookmarked, &["alt+o"]),
+    (Action::SplitDown, &["alt+\""]),
+    (Action::SplitRight, &["alt+%"]),
+    (Action::ClosePane, &["alt+q"]),
+    (Action::NextPane, &["alt+;"]),
+    (Action::PreviousPane, &["alt+:"]),
+    (Action::GrowPane, &["alt+="]),
+    (Action::ShrinkPane, &["alt+-"]),
+    (Action::SaveView, &["alt+T"]),
+    (Action::RenameTab, &["alt+R"]),
+    (Action::CloseTab, &["alt+Q"]),
+    (Action::NextTab, &["ctrl+right"]),
+    (Action::PreviousTab, &["ctrl+left"]),
+    (Action::ReplayPause, &["alt+p"]),
+    (Action::ReplayStep, &["alt+n"]),
+    (Action::ReplayFaster, &["alt+up"]),
+    (Action::ReplaySlower, &["alt+down"]),
+    (Action::ReplayForward, &["alt+right"]),
+    (Action::ReplayBackward, &["alt+left"]),
+];
+
+const VIM_KEYS: &[(Action, &[&str])] = &[
+    (Action::ScrollDown, &["ctrl+e", "ctrl+d"]),
+    (Action::ScrollUp, &["ctrl+y", "ctrl+u"]),
+    (Action::PageDown, &["ctrl+f", "pagedown"]),
+    (Action::PageUp, &["ctrl+b", "pageup"]),
+    // moved out of the way
+    (Action::RatePanel, &["alt+e"]),
+    (Action::ExpandDuplicates, &["alt+D"]),
+    (Action::Breakdown, &["alt+i"]),
+    (Action::ToggleScrollbar, &["alt+z"]),
+];
+
+const EMACS_KEYS: &[(Action, &[&str])] = &[
+    (Action::Up, &["up", "ctrl+p"]),
+    (Action::Down, &["down", "ctrl+n"]),
+    (Action::PageUp, &["alt+v", "pageup"]),
+    (Action::PageDown, &["ctrl+v", "pagedown"]),
+    (Action::Top, &["alt+<", "ctrl+t"]),
+    (Action::AutoScroll, &["alt+>", "ctrl+a"]),
+    // moved out of the way
+    (Action::Select, &["ctrl+space"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> KeyBinding {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_keys_with_modifiers() {
+        assert_eq!(
+            key("ctrl+c"),
+            KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            key("ctrl++"),
+            KeyBinding::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            key("alt+<"),
+            KeyBinding::new(KeyCode::Char('<'), KeyModifiers::ALT)
+        );
+        assert_eq!(
+            key("Alt+Shift+Up"),
+            KeyBinding::new(KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            key("f10"),
+            KeyBinding::new(KeyCode::F(10), KeyModifiers::NONE)
+        );
+    }
+
+    #[test]
+    fn shift_with_a_char_is_the_uppercase_char() {
+        assert_eq!(key("alt+shift+g"), key("alt+G"));
+        let event = KeyEvent::new(KeyCode::Char('T'), KeyModifiers::ALT | KeyModifiers::SHIFT);
+        assert_eq!(KeyBinding::from(&event), key("alt+T"));
+        assert_eq!(key("alt+G").to_string(), "alt+G");
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        for s in ["ctrl+foo", "hyper+a", "f13", "", "ctrl+"] {
+            assert!(s.parse::<KeyBinding>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn presets_have_no_conflicts() {
+        for preset in [Preset::Default, Preset::Vim, Preset::Emacs] {
+            assert_eq!(Keymap::preset(preset).conflicts(), Vec::<String>::new());
+        }
+    }
+
+    #[test]
+    fn search_input_keys_are_conflicts() {
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::Top, vec![key("ctrl+w")]);
+        assert_eq!(keymap.conflicts().len(), 1);
+    }
+
+    #[test]
+    fn action_names_round_trip() {
+        for action in Action::ALL {
+            assert_eq!(Action::from_name(action.name()), Some(action));
+        }
+    }
+}
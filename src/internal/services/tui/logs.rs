@@ -1,36 +1,136 @@
-use ansi_to_tui::IntoText as _;
 use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ops::{Range, RangeInclusive};
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthChar;
 
 use ratatui::{
     prelude::*,
-    style::{Color, Style},
     text::{Line, Span},
     widgets::Paragraph,
 };
 
-use crate::internal::common::log_err;
+use crate::internal::common::strip_ansi;
+use crate::internal::domain::dedup::{Dedup, Group};
+use crate::internal::domain::record::{Record, TimeSource};
 use crate::internal::domain::records::Records;
+use crate::internal::domain::redact::Redactor;
+use crate::internal::services::tui::render::{
+    Highlight, LevelColoring, RenderMode, find_matches, render_record, style_ranges,
+};
+use crate::internal::services::tui::table::Table;
+use crate::internal::services::tui::theme::Theme;
 
-pub struct Logs {
-    records: Records,
+/// How far `Shift+Left/Right` scrolls horizontally.
+const HORIZONTAL_SCROLL_STEP: usize = 8;
+
+/// Which scrollbar a mouse press landed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollbarHit {
+    Vertical,
+    Horizontal,
+}
+
+/// Width of the column marking bookmarked records.
+const BOOKMARK_GUTTER: usize = 2;
+
+/// Where a pane is in the records: scroll, cursor and what the last render showed.
+pub struct View {
     lines_len: usize,
     vertical_scroll: usize,
     vertical_scroll_state: ratatui::widgets::ScrollbarState,
     area_height: usize,
     auto_scroll: bool,
-    show_scrollbar: bool,
+    horizontal_scroll: usize,
+    max_horizontal_scroll: usize,
+    /// Index of the record under the line cursor, `None` when there is no cursor.
+    cursor: Option<usize>,
+    /// Shown records and the lines they take, as of the last render.
+    entries: Vec<(usize, Range<usize>)>,
+    /// Runs of duplicates (first to last record index), as of the last render.
+    groups: Vec<RangeInclusive<usize>>,
+    /// Scroll to the cursor on the next render, once the area is known.
+    follow_cursor: bool,
+    /// Index of the record the visual selection started at, it ends at the cursor.
+    selection: Option<usize>,
+    /// Jump to the next (`true`) or previous search match on the next render.
+    jump_to_match: Option<bool>,
+    /// Width the lines were wrapped and cut to on the last render.
+    content_width: usize,
+    /// Where the lines went on the last render, for mouse clicks.
+    lines_area: Rect,
+    horizontal_scrollbar_area: Option<Rect>,
 }
 
-impl Logs {
-    pub fn new(records: Records) -> Self {
+impl Default for View {
+    fn default() -> Self {
         Self {
-            records,
             lines_len: 0,
             vertical_scroll: 0,
             vertical_scroll_state: ratatui::widgets::ScrollbarState::new(0),
             area_height: 0,
             auto_scroll: true,
+            horizontal_scroll: 0,
+            max_horizontal_scroll: 0,
+            cursor: None,
+            entries: vec![],
+            groups: vec![],
+            follow_cursor: false,
+            selection: None,
+            jump_to_match: None,
+            content_width: 0,
+            lines_area: Rect::default(),
+            horizontal_scrollbar_area: None,
+        }
+    }
+}
+
+pub struct Logs {
+    records: Records,
+    view: View,
+    show_scrollbar: bool,
+    render_mode: RenderMode,
+    level_coloring: LevelColoring,
+    theme: Theme,
+    table: Table,
+    table_view: bool,
+    dedup: Dedup,
+    /// Index of the first record of runs showing every duplicate.
+    expanded_duplicates: HashSet<usize>,
+    redactor: Redactor,
+    reveal_secrets: bool,
+    highlights: Vec<Highlight>,
+    show_highlights: bool,
+    /// Separators between records further apart, `None` turns them off.
+    gap_threshold: Option<Duration>,
+    gap_time: TimeSource,
+    show_gaps: bool,
+    /// Wrap long lines, otherwise truncate them and scroll sideways.
+    wrap_lines: bool,
+}
+
+impl Logs {
+    pub fn new(records: Records) -> Self {
+        Self {
+            records,
+            view: View::default(),
             show_scrollbar: true,
+            render_mode: RenderMode::default(),
+            level_coloring: LevelColoring::default(),
+            theme: Theme::default(),
+            table: Table::default(),
+            table_view: false,
+            dedup: Dedup::default(),
+            expanded_duplicates: HashSet::new(),
+            redactor: Redactor::default(),
+            reveal_secrets: false,
+            highlights: vec![],
+            show_highlights: true,
+            gap_threshold: None,
+            gap_time: TimeSource::default(),
+            show_gaps: true,
+            wrap_lines: true,
         }
     }
     pub fn records(&self) -> &Records {
@@ -40,7 +140,7 @@ impl Logs {
         &mut self.records
     }
     fn bottom_scroll_amount(&self) -> Option<u32> {
-        let amount = self.lines_len as i32 - self.area_height as i32 - 1;
+        let amount = self.view.lines_len as i32 - self.view.area_height as i32 - 1;
         if amount >= 0 {
             return Some(amount as u32);
         }
@@ -48,7 +148,7 @@ impl Logs {
     }
     fn scroll_down_disabled(&self) -> bool {
         if let Some(max_scroll) = self.bottom_scroll_amount() {
-            if self.vertical_scroll > max_scroll as usize {
+            if self.view.vertical_scroll > max_scroll as usize {
                 // we are already at the bottom
                 return true;
             } else {
@@ -60,18 +160,394 @@ impl Logs {
     pub fn set_show_scrollbar(&mut self, val: bool) {
         self.show_scrollbar = val
     }
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+    pub fn cycle_render_mode(&mut self) {
+        self.render_mode = self.render_mode.next();
+    }
+    pub fn set_level_coloring(&mut self, val: LevelColoring) {
+        self.level_coloring = val
+    }
+    pub fn set_theme(&mut self, val: Theme) {
+        self.theme = val
+    }
+    /// Puts `view` in place of the current one, which `view` gets.
+    pub fn swap_view(&mut self, view: &mut View) {
+        std::mem::swap(&mut self.view, view);
+    }
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+    pub fn table_mut(&mut self) -> &mut Table {
+        &mut self.table
+    }
+    pub fn table_view(&self) -> bool {
+        self.table_view
+    }
+    pub fn toggle_table_view(&mut self) {
+        self.table_view = !self.table_view;
+        self.view.horizontal_scroll = 0;
+    }
+    pub fn set_redactor(&mut self, redactor: Redactor) {
+        self.redactor = redactor;
+        self.records.set_redactor(self.redactor().cloned());
+    }
+    /// Secrets masker, `None` while secrets are revealed.
+    pub fn redactor(&self) -> Option<&Redactor> {
+        (!self.reveal_secrets).then_some(&self.redactor)
+    }
+    pub fn reveal_secrets(&self) -> bool {
+        self.reveal_secrets
+    }
+    pub fn toggle_reveal_secrets(&mut self) {
+        self.reveal_secrets = !self.reveal_secrets;
+        self.records.set_redactor(self.redactor().cloned());
+    }
+    pub fn set_highlights(&mut self, highlights: Vec<Highlight>) {
+        self.highlights = highlights;
+    }
+    pub fn toggle_highlights(&mut self) {
+        self.show_highlights = !self.show_highlights;
+    }
+    pub fn set_gaps(&mut self, threshold: Option<Duration>, time: TimeSource) {
+        self.gap_threshold = threshold;
+        self.gap_time = time;
+    }
+    pub fn toggle_gaps(&mut self) {
+        self.show_gaps = !self.show_gaps;
+    }
+    pub fn cursor(&self) -> Option<usize> {
+        self.view.cursor
+    }
+    pub fn cursor_record(&self) -> Option<&Record> {
+        self.view.cursor.and_then(|index| self.records.get(index))
+    }
+    /// Shows the line cursor on the last visible record, or hides it.
+    pub fn toggle_cursor(&mut self) {
+        if self.view.cursor.take().is_some() {
+            self.view.selection = None;
+            return;
+        }
+        self.view.cursor = self.last_visible_record();
+    }
+    /// Index of the record on the last visible line.
+    fn last_visible_record(&self) -> Option<usize> {
+        let last_visible = (self.view.vertical_scroll + self.view.area_height)
+            .min(self.view.lines_len)
+            .saturating_sub(1);
+        self.view
+            .entries
+            .iter()
+            .find(|(_, lines)| lines.contains(&last_visible))
+            .or(self.view.entries.last())
+            .map(|(index, _)| *index)
+    }
+    /// Moves the cursor `by` records down (or up if negative) and scrolls to it.
+    pub fn move_cursor(&mut self, by: isize) {
+        let Some(cursor) = self.view.cursor else {
+            return;
+        };
+        let Some(position) = self
+            .view
+            .entries
+            .iter()
+            .position(|(index, _)| *index == cursor)
+        else {
+            return;
+        };
+        let position = position
+            .saturating_add_signed(by)
+            .min(self.view.entries.len() - 1);
+        self.view.cursor = Some(self.view.entries[position].0);
+        self.view.auto_scroll = false;
+        self.view.follow_cursor = true;
+    }
+    /// Keeps the cursor visible, e.g. when a pane opens next to the logs.
+    pub fn follow_cursor(&mut self) {
+        self.view.follow_cursor = self.view.cursor.is_some();
+    }
+    /// Moves the cursor to the next shown bookmark (or previous if not `forward`),
+    /// wrapping around, and scrolls to it.
+    pub fn jump_to_bookmark(&mut self, forward: bool) {
+        let first_visible = self
+            .view
+            .entries
+            .iter()
+            .find(|(_, lines)| lines.end > self.view.vertical_scroll)
+            .map(|(index, _)| *index);
+        let Some(current) = self.view.cursor.or(first_visible) else {
+            return;
+        };
+        let mut bookmarks = self
+            .view
+            .entries
+            .iter()
+            .map(|(index, _)| *index)
+            .filter(|index| self.records.is_bookmarked(*index));
+        let target = if forward {
+            let first = bookmarks.clone().next();
+            bookmarks.find(|index| *index > current).or(first)
+        } else {
+            let last = bookmarks.clone().next_back();
+            bookmarks.rev().find(|index| *index < current).or(last)
+        };
+        if target.is_some() {
+            self.jump_to(target);
+        }
+    }
+    /// Puts the cursor on the record at `index` and scrolls to it, on the next render.
+    pub fn jump_to(&mut self, index: Option<usize>) {
+        self.view.cursor = index;
+        self.view.auto_scroll = false;
+        self.view.follow_cursor = true;
+    }
+    /// Indexes of the records from the selection start to the cursor.
+    pub fn selection(&self) -> Option<RangeInclusive<usize>> {
+        let (start, cursor) = (self.view.selection?, self.view.cursor?);
+        Some(start.min(cursor)..=start.max(cursor))
+    }
+    /// Starts a visual selection at the cursor, showing the cursor if needed, or drops it.
+    pub fn toggle_selection(&mut self) {
+        if self.view.selection.take().is_some() {
+            return;
+        }
+        if self.view.cursor.is_none() {
+            self.toggle_cursor();
+        }
+        self.view.selection = self.view.cursor;
+    }
+    pub fn clear_selection(&mut self) {
+        self.view.selection = None;
+    }
+    /// Filtered records in the selection, or the one under the cursor,
+    /// without ANSI colors and with secrets masked, one per line.
+    pub fn selected_text(&self) -> Option<String> {
+        let selection = self
+            .selection()
+            .or_else(|| self.view.cursor.map(|cursor| cursor..=cursor))?;
+        Some(
+            self.text(
+                self.records
+                    .indexed()
+                    .filter(|(index, _)| selection.contains(index))
+                    .map(|(_, record)| record),
+            ),
+        )
+    }
+    /// All filtered records, like [`Logs::selected_text`].
+    pub fn filtered_text(&self) -> String {
+        self.text(self.records.indexed().map(|(_, record)| record))
+    }
+    /// Secrets stay masked even while revealed on screen, copies leave the app.
+    fn text<'a>(&self, records: impl Iterator<Item = &'a Record>) -> String {
+        let mut text = String::new();
+        for record in records {
+            let value = record.to_string();
+            let value = self.redactor.redact(&value);
+            text.push_str(strip_ansi(&value).trim_end_matches(['\n', '\r']));
+            text.push('\n');
+        }
+        text
+    }
+    /// Scrolls just enough for `lines` to be visible, their start if they don't fit.
+    fn scroll_to_lines(&mut self, lines: Range<usize>) {
+        self.view.auto_scroll = false;
+        if lines.end > self.view.vertical_scroll + self.view.area_height {
+            self.view.vertical_scroll = lines.end.saturating_sub(self.view.area_height);
+        }
+        if lines.start < self.view.vertical_scroll {
+            self.view.vertical_scroll = lines.start;
+        }
+        self.view.vertical_scroll_state = self
+            .view
+            .vertical_scroll_state
+            .position(self.view.vertical_scroll);
+    }
+    pub fn dedup(&self) -> Dedup {
+        self.dedup
+    }
+    pub fn cycle_dedup(&mut self) {
+        self.dedup = self.dedup.next();
+        self.expanded_duplicates.clear();
+    }
+    /// Expands or collapses the duplicates under the cursor, or on the last visible line.
+    pub fn toggle_expand_duplicates(&mut self) {
+        let Some(index) = self.view.cursor.or_else(|| self.last_visible_record()) else {
+            return;
+        };
+        let Some(group) = self.view.groups.iter().find(|group| group.contains(&index)) else {
+            return;
+        };
+        let first = *group.start();
+        if !self.expanded_duplicates.remove(&first) {
+            self.expanded_duplicates.insert(first);
+        } else if self.view.cursor.is_some() {
+            // the cursor would be on a hidden duplicate
+            self.view.cursor = Some(first);
+            self.view.selection = None;
+        }
+    }
+    /// Collapses every run of duplicates, e.g. when the records they refer to are dropped.
+    pub fn collapse_duplicates(&mut self) {
+        self.expanded_duplicates.clear();
+    }
+    pub fn wrap_lines(&self) -> bool {
+        self.wrap_lines
+    }
+    pub fn toggle_wrap_lines(&mut self) {
+        self.wrap_lines = !self.wrap_lines;
+        self.view.horizontal_scroll = 0;
+        self.follow_cursor();
+    }
+    /// Moves the cursor to the next record with a search match (or previous if not `forward`),
+    /// scrolling sideways to the match when lines aren't wrapped.
+    pub fn jump_to_match(&mut self, forward: bool) {
+        if !self.records.filter_key().is_empty() {
+            self.view.jump_to_match = Some(forward);
+        }
+    }
+    /// First search match in records after (or before) the cursor, wrapping around,
+    /// as the record index, the line and the columns of the match.
+    fn find_match(&self, lines: &[Line], forward: bool) -> Option<(usize, usize, Range<usize>)> {
+        let key = self.records.filter_key().as_ref();
+        let count = self.view.entries.len();
+        let current = self
+            .view
+            .entries
+            .iter()
+            .position(|(index, _)| Some(*index) == self.view.cursor);
+        let first_visible = self
+            .view
+            .entries
+            .iter()
+            .position(|(_, lines)| lines.end > self.view.vertical_scroll)
+            .unwrap_or(0);
+        // without a cursor the first visible record is a candidate too
+        let order: Vec<usize> = match (forward, current) {
+            (true, Some(current)) => (current + 1..count).chain(0..=current).collect(),
+            (true, None) => (first_visible..count).chain(0..first_visible).collect(),
+            (false, Some(current)) => (0..current).rev().chain((current..count).rev()).collect(),
+            (false, None) => (0..=first_visible)
+                .rev()
+                .chain((first_visible + 1..count).rev())
+                .collect(),
+        };
+        order.into_iter().find_map(|position| {
+            let (index, range) = self.view.entries.get(position)?;
+            range.clone().find_map(|i| {
+                let line = lines.get(i)?;
+                let text: String = line
+                    .spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect();
+                let found = find_matches(&text, key).into_iter().next()?;
+                let column = |chars: usize| -> usize {
+                    text.chars()
+                        .take(chars)
+                        .map(|c| c.width().unwrap_or(0))
+                        .sum()
+                };
+                Some((*index, i, column(found.start)..column(found.end)))
+            })
+        })
+    }
+    pub fn lines_area(&self) -> Rect {
+        self.view.lines_area
+    }
+    /// Puts the line cursor on the record shown at `row`, returns whether there is one.
+    pub fn click(&mut self, row: u16) -> bool {
+        let line = self.view.vertical_scroll + row.saturating_sub(self.view.lines_area.y) as usize;
+        let clicked = self
+            .view
+            .entries
+            .iter()
+            .find(|(_, lines)| lines.contains(&line))
+            .map(|(index, _)| *index);
+        if clicked.is_some() {
+            self.view.cursor = clicked;
+        }
+        clicked.is_some()
+    }
+    pub fn scrollbar_at(&self, column: u16, row: u16) -> Option<ScrollbarHit> {
+        let area = self.view.lines_area;
+        if self.show_scrollbar
+            && column == area.right().saturating_sub(1)
+            && (area.top()..area.bottom()).contains(&row)
+        {
+            return Some(ScrollbarHit::Vertical);
+        }
+        self.view
+            .horizontal_scrollbar_area
+            .filter(|area| area.contains(Position::new(column, row)))
+            .map(|_| ScrollbarHit::Horizontal)
+    }
+    /// Scrolls so the thumb of the dragged scrollbar is under the mouse.
+    pub fn drag_scrollbar(&mut self, hit: ScrollbarHit, column: u16, row: u16) {
+        // where the mouse is on the track, between the arrows, from 0 to 1
+        let fraction = |position: u16, start: u16, length: u16| {
+            let track = length.saturating_sub(3).max(1) as f64;
+            let offset = position.saturating_sub(start + 1).min(track as u16) as f64;
+            offset / track
+        };
+        match hit {
+            ScrollbarHit::Vertical => {
+                let area = self.view.lines_area;
+                let fraction = fraction(row, area.y, area.height);
+                if fraction >= 1.0 {
+                    self.set_auto_scroll();
+                    return;
+                }
+                let max_scroll = self.view.lines_len.saturating_sub(self.view.area_height);
+                self.view.auto_scroll = false;
+                self.view.vertical_scroll = (fraction * max_scroll as f64).round() as usize;
+                self.view.vertical_scroll_state = self
+                    .view
+                    .vertical_scroll_state
+                    .position(self.view.vertical_scroll);
+            }
+            ScrollbarHit::Horizontal => {
+                let Some(area) = self.view.horizontal_scrollbar_area else {
+                    return;
+                };
+                let fraction = fraction(column, area.x, area.width);
+                self.view.horizontal_scroll =
+                    (fraction * self.view.max_horizontal_scroll as f64).round() as usize;
+            }
+        }
+    }
+    pub fn scroll_left(&mut self) {
+        self.view.horizontal_scroll = self
+            .view
+            .horizontal_scroll
+            .saturating_sub(HORIZONTAL_SCROLL_STEP);
+    }
+    pub fn scroll_right(&mut self) {
+        self.view.horizontal_scroll = self
+            .view
+            .horizontal_scroll
+            .saturating_add(HORIZONTAL_SCROLL_STEP)
+            .min(self.view.max_horizontal_scroll);
+    }
     pub fn set_auto_scroll(&mut self) {
-        self.auto_scroll = true;
+        self.view.auto_scroll = true;
         self.scroll_to_bottom();
     }
     pub fn scroll_to_top(&mut self) {
-        self.vertical_scroll = 0;
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.view.vertical_scroll = 0;
+        self.view.vertical_scroll_state = self
+            .view
+            .vertical_scroll_state
+            .position(self.view.vertical_scroll);
     }
     pub fn scroll_to_bottom(&mut self) {
         if let Some(max_scroll) = self.bottom_scroll_amount() {
-            self.vertical_scroll = max_scroll as usize + 1;
-            self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+            self.view.vertical_scroll = max_scroll as usize + 1;
+            self.view.vertical_scroll_state = self
+                .view
+                .vertical_scroll_state
+                .position(self.view.vertical_scroll);
         } else {
             self.scroll_to_top();
         }
@@ -80,90 +556,100 @@ impl Logs {
         if self.scroll_down_disabled() {
             return;
         }
-        self.auto_scroll = false;
-        self.vertical_scroll = self.vertical_scroll.saturating_add(1);
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.view.auto_scroll = false;
+        self.view.vertical_scroll = self.view.vertical_scroll.saturating_add(1);
+        self.view.vertical_scroll_state = self
+            .view
+            .vertical_scroll_state
+            .position(self.view.vertical_scroll);
     }
     pub fn scroll_down_many(&mut self) {
         if self.scroll_down_disabled() {
             return;
         }
-        self.auto_scroll = false;
-        self.vertical_scroll = self.vertical_scroll.saturating_add(self.area_height);
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.view.auto_scroll = false;
+        self.view.vertical_scroll = self
+            .view
+            .vertical_scroll
+            .saturating_add(self.view.area_height);
+        self.view.vertical_scroll_state = self
+            .view
+            .vertical_scroll_state
+            .position(self.view.vertical_scroll);
     }
     pub fn scroll_up(&mut self) {
-        self.auto_scroll = false;
-        self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.view.auto_scroll = false;
+        self.view.vertical_scroll = self.view.vertical_scroll.saturating_sub(1);
+        self.view.vertical_scroll_state = self
+            .view
+            .vertical_scroll_state
+            .position(self.view.vertical_scroll);
     }
     pub fn scroll_up_many(&mut self) {
-        self.auto_scroll = false;
-        self.vertical_scroll = self.vertical_scroll.saturating_sub(self.area_height);
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.view.auto_scroll = false;
+        self.view.vertical_scroll = self
+            .view
+            .vertical_scroll
+            .saturating_sub(self.view.area_height);
+        self.view.vertical_scroll_state = self
+            .view
+            .vertical_scroll_state
+            .position(self.view.vertical_scroll);
     }
 
+    /// Applies highlight rules, then marks search matches on top.
     fn mark_result(&self, line: &mut Line) {
-        if self.records.filter_key().is_empty() {
-            return;
-        }
-        let text = &line.spans[0].content;
-        if text.is_empty() {
+        let highlights = if self.show_highlights {
+            &self.highlights[..]
+        } else {
+            &[]
+        };
+        if self.records.filter_key().is_empty() && highlights.is_empty() {
             return;
         }
-
-        let found_style = Style::new().bg(Color::Yellow).fg(Color::Black);
-        let not_found_style = Style::new().white();
-        let mut k = 0;
-        let mut spans: Vec<Span<'_>> = vec![];
-        let mut span_not_found = String::from("");
-        let mut span_found = String::from("");
-
-        for c in text.chars() {
-            if c.to_ascii_lowercase()
-                == self
-                    .records
-                    .filter_key()
-                    .as_ref()
-                    .to_lowercase()
-                    .chars()
-                    .nth(k)
-                    .unwrap()
-            {
-                span_found.push(c);
-                if k == (self.records.filter_key().as_ref().len() - 1) {
-                    if !span_not_found.is_empty() {
-                        spans.push(Span::styled(span_not_found.clone(), not_found_style));
-                        span_not_found = String::from("");
-                    }
-                    if !span_found.is_empty() {
-                        spans.push(Span::styled(span_found.clone(), found_style));
-                        span_found = String::from("");
-                    }
-                    k = 0;
-                } else {
-                    k += 1;
-                }
-            } else {
-                k = 0;
-                if !span_found.is_empty() {
-                    span_not_found.push_str(&span_found);
-                    span_found = String::from("");
-                }
-                span_not_found.push(c);
-            }
-        }
-
-        if !span_not_found.is_empty() {
-            spans.push(Span::styled(span_not_found.clone(), not_found_style));
-        }
-        if !span_found.is_empty() {
-            spans.push(Span::styled(span_found.clone(), not_found_style));
+        let text: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        for highlight in highlights {
+            style_ranges(line, &highlight.find(&text), highlight.style);
         }
+        let ranges = find_matches(&text, self.records.filter_key().as_ref());
+        style_ranges(line, &ranges, self.theme.found);
+    }
+}
+/// `──── 42s later ────` between records far apart.
+fn gap_separator(gap: Duration, width: usize, theme: &Theme) -> Line<'static> {
+    let label = format!(" {} later ", format_duration(gap));
+    let side = width.saturating_sub(label.chars().count()) / 2;
+    Line::styled(
+        format!("{}{}{}", "─".repeat(side), label, "─".repeat(side)),
+        theme.muted,
+    )
+}
 
-        line.spans = spans
+/// `42s`, `3m05s`, `1h02m`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
     }
 }
+
+/// `×148 12:00:01…12:03:20` after a collapsed record.
+fn badge(group: &Group, theme: &Theme) -> [Span<'static>; 3] {
+    let (first, last) = group.seen();
+    let format = |time| chrono::DateTime::<chrono::Local>::from(time).format("%H:%M:%S");
+    [
+        Span::raw(" "),
+        Span::styled(format!("×{}", group.records.len()), theme.badge),
+        Span::styled(format!(" {}…{}", format(first), format(last)), theme.muted),
+    ]
+}
+
 impl Default for Logs {
     fn default() -> Self {
         Logs::new(Records::default())
@@ -173,7 +659,11 @@ fn wrap(line: Line, width: usize) -> impl Iterator<Item = Line> {
     let mut line = line;
     std::iter::from_fn(move || {
         if line.width() > width {
-            let (first, second) = line_split_at(line.clone(), width);
+            let (mut first, mut second) = line_split_at(line.clone(), width);
+            if first.width() == 0 {
+                // a wide char doesn't fit at all, take it anyway to make progress
+                (first, second) = line_split_at(line.clone(), first_char_width(&line));
+            }
             line = second;
             Some(first)
         } else if line.width() > 0 {
@@ -186,8 +676,19 @@ fn wrap(line: Line, width: usize) -> impl Iterator<Item = Line> {
     })
 }
 
+/// Splits the span after `mid` columns of display width.
 fn span_split_at(span: Span, mid: usize) -> (Span, Span) {
-    let (first, second) = span.content.split_at(mid);
+    let mut width = 0;
+    let index = span
+        .content
+        .char_indices()
+        .find(|(_, c)| {
+            width += c.width().unwrap_or(0);
+            width > mid
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(span.content.len());
+    let (first, second) = span.content.split_at(index);
     let first = Span {
         content: Cow::Owned(first.into()),
         style: span.style,
@@ -206,76 +707,342 @@ fn line_split_at(line: Line, mid: usize) -> (Line, Line) {
     second.alignment = line.alignment;
     for span in line.spans {
         let first_width = first.width();
-        let span_width = span.width();
-        if first_width + span_width <= mid {
+        if !second.spans.is_empty() {
+            second.spans.push(span);
+        } else if first_width + span.width() <= mid {
             first.spans.push(span);
-        } else if first_width < mid && first_width + span_width > mid {
-            let span_mid = mid - first_width;
-            let (span_first, span_second) = span_split_at(span, span_mid);
+        } else {
+            let (span_first, span_second) = span_split_at(span, mid - first_width);
             first.spans.push(span_first);
             second.spans.push(span_second);
-        } else {
-            second.spans.push(span);
         }
     }
     (first, second)
 }
+
+/// Part of the line `width` columns wide, starting at column `offset`.
+fn clip(line: Line, offset: usize, width: usize) -> Line {
+    let (_, rest) = line_split_at(line, offset);
+    line_split_at(rest, width).0
+}
+
+/// Like [`clip`], with `‹` and `›` where the line goes on past the edges.
+fn truncate<'a>(line: Line<'a>, offset: usize, width: usize, theme: &Theme) -> Line<'a> {
+    let line_width = line.width();
+    let hidden_left = offset > 0 && line_width > 0;
+    let hidden_right = line_width > offset + width;
+    if width < 2 || (!hidden_left && !hidden_right) {
+        return clip(line, offset, width);
+    }
+
+    let marker = |symbol| Span::styled(symbol, theme.muted);
+    let start = if hidden_left { offset + 1 } else { offset };
+    let end = if hidden_right {
+        offset + width - 1
+    } else {
+        offset + width
+    };
+    let mut truncated = clip(line, start, end.saturating_sub(start));
+    if hidden_left {
+        truncated.spans.insert(0, marker("‹"));
+    }
+    if hidden_right {
+        // a wide char may leave a column free before the marker
+        let pad = width - 1 - truncated.width();
+        truncated.spans.push(Span::raw(" ".repeat(pad)));
+        truncated.spans.push(marker("›"));
+    }
+    truncated
+}
+
+fn first_char_width(line: &Line) -> usize {
+    line.spans
+        .iter()
+        .flat_map(|span| span.content.chars())
+        .filter_map(|c| c.width())
+        .find(|width| *width > 0)
+        .unwrap_or(1)
+}
 impl ratatui::widgets::Widget for &mut Logs {
     fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
     where
         Self: Sized,
     {
-        self.area_height = area.height.into();
+        let mut area = area;
+        let gutter = if self.records.bookmarks().is_empty() {
+            0
+        } else {
+            BOOKMARK_GUTTER
+        };
+        let content_width = (area.width as usize).saturating_sub(2 + gutter);
+
+        // the record on top and how far into it, to keep it there when the width changes
+        let anchor = if !self.view.auto_scroll && content_width != self.view.content_width {
+            self.view
+                .entries
+                .iter()
+                .find(|(_, lines)| lines.end > self.view.vertical_scroll)
+                .map(|(index, lines)| {
+                    (
+                        *index,
+                        self.view.vertical_scroll.saturating_sub(lines.start),
+                    )
+                })
+        } else {
+            None
+        };
+        self.view.content_width = content_width;
 
         // **MUST BE THE FIRST STEP**
         // Build lines and run autoscroll
 
         let mut lines = vec![];
-        for record in self.records.iter() {
-            // can't use let line = Line::from(record.to_string());
-            // because ANSI is not parsed properly
-            let text = record
-                .to_string()
-                .into_text()
-                .unwrap_or(log_err("Error processing log").into());
-            for line in text.lines {
-                let wrapped_lines: Vec<_> = wrap(line, area.width as usize - 2).collect();
-                for mut wl in wrapped_lines {
-                    self.mark_result(&mut wl);
-                    lines.push(wl);
+        let mut entries = vec![];
+        let mut table_header = None;
+        self.view.groups.clear();
+        if self.table_view {
+            let (header, rows) = self.table.lines(
+                &self.records,
+                self.level_coloring,
+                self.redactor(),
+                &self.theme,
+            );
+            // header stays on top while scrolling
+            let [header_area, rows_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+            table_header = Some((header, header_area));
+            area = rows_area;
+
+            for ((index, _), mut line) in self.records.indexed().zip(rows) {
+                self.mark_result(&mut line);
+                entries.push((index, lines.len()..lines.len() + 1));
+                lines.push(line);
+            }
+        } else {
+            let gap_threshold = self.gap_threshold.filter(|_| self.show_gaps);
+            let mut previous = None;
+            for group in self.dedup.groups(self.records.indexed()) {
+                let count = group.records.len();
+                let first = group.records[0].0;
+                if count > 1 {
+                    self.view.groups.push(first..=group.records[count - 1].0);
+                }
+                let shown = if self.expanded_duplicates.contains(&first) {
+                    count
+                } else {
+                    1
+                };
+                for (i, (index, record)) in group.records.iter().enumerate() {
+                    let time = record.time(self.gap_time);
+                    let gap = previous.and_then(|previous| time.duration_since(previous).ok());
+                    previous = Some(time);
+                    if i >= shown {
+                        continue;
+                    }
+                    if let (Some(gap), Some(threshold)) = (gap, gap_threshold)
+                        && gap > threshold
+                    {
+                        lines.push(gap_separator(gap, content_width, &self.theme));
+                    }
+
+                    let record = match self.redactor() {
+                        Some(redactor) => redactor.redact_record(record),
+                        None => Cow::Borrowed(*record),
+                    };
+                    let text =
+                        render_record(&record, self.render_mode, self.level_coloring, &self.theme);
+                    let last = text.lines.len().saturating_sub(1);
+                    let start = lines.len();
+                    for (j, mut line) in text.lines.into_iter().enumerate() {
+                        self.mark_result(&mut line);
+                        if count > 1 && i == 0 && j == last {
+                            line.spans.extend(badge(&group, &self.theme));
+                        }
+                        if self.wrap_lines {
+                            lines.extend(wrap(line, content_width));
+                        } else {
+                            lines.push(line);
+                        }
+                    }
+                    entries.push((*index, start..lines.len()));
                 }
             }
         }
 
-        self.lines_len = lines.len();
+        // jump to a search match, sideways too
+        let scrolls_sideways = self.table_view || !self.wrap_lines;
+        let mut match_line = None;
+        if let Some(forward) = self.view.jump_to_match.take() {
+            // entries are what the jump starts from
+            self.view.entries = std::mem::take(&mut entries);
+            if let Some((index, line, columns)) = self.find_match(&lines, forward) {
+                self.view.cursor = Some(index);
+                self.view.auto_scroll = false;
+                match_line = Some(line);
+                if scrolls_sideways
+                    && (columns.start < self.view.horizontal_scroll
+                        || columns.end > self.view.horizontal_scroll + content_width)
+                {
+                    self.view.horizontal_scroll = columns.start.saturating_sub(content_width / 4);
+                }
+            }
+            entries = std::mem::take(&mut self.view.entries);
+        }
+
+        // cut lines to the width, scrolled sideways
+        if scrolls_sideways {
+            let width = lines
+                .iter()
+                .chain(table_header.as_ref().map(|(header, _)| header))
+                .map(Line::width)
+                .max();
+            self.view.max_horizontal_scroll = width.unwrap_or(0).saturating_sub(content_width);
+            self.view.horizontal_scroll = self
+                .view
+                .horizontal_scroll
+                .min(self.view.max_horizontal_scroll);
+            let offset = self.view.horizontal_scroll;
+            lines = lines
+                .into_iter()
+                .map(|line| {
+                    if self.table_view {
+                        clip(line, offset, content_width)
+                    } else {
+                        truncate(line, offset, content_width, &self.theme)
+                    }
+                })
+                .collect();
+        } else {
+            self.view.max_horizontal_scroll = 0;
+        }
+        if let Some((header, header_area)) = table_header {
+            let mut header = clip(header, self.view.horizontal_scroll, content_width);
+            header.spans.insert(0, Span::raw(" ".repeat(gutter)));
+            Paragraph::new(header).render(header_area, buf);
+        }
+
+        // horizontal scrollbar under the lines, when they are wider than the area
+        let mut horizontal_scrollbar_area = None;
+        if self.show_scrollbar && scrolls_sideways && self.view.max_horizontal_scroll > 0 {
+            let [lines_area, scrollbar_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+            area = lines_area;
+            horizontal_scrollbar_area = Some(scrollbar_area);
+        }
+
+        self.view.lines_area = area;
+        self.view.horizontal_scrollbar_area = horizontal_scrollbar_area;
+
+        // keep the cursor on a shown record, the next one if it went away
+        if let Some(cursor) = self.view.cursor
+            && !entries.iter().any(|(index, _)| *index == cursor)
+        {
+            self.view.cursor = entries
+                .iter()
+                .find(|(index, _)| *index > cursor)
+                .or(entries.last())
+                .map(|(index, _)| *index);
+        }
+        if let Some(selection) = self.selection() {
+            for (_, range) in entries
+                .iter()
+                .filter(|(index, _)| selection.contains(index))
+            {
+                for line in &mut lines[range.clone()] {
+                    line.style = line.style.patch(self.theme.selection);
+                }
+            }
+        }
+        if let Some((_, range)) = entries
+            .iter()
+            .find(|(index, _)| Some(*index) == self.view.cursor)
+        {
+            for line in &mut lines[range.clone()] {
+                line.style = line.style.patch(self.theme.cursor);
+            }
+        }
+        // gutter marking bookmarked records, only once there are bookmarks
+        if !self.records.bookmarks().is_empty() {
+            let marked: Vec<usize> = entries
+                .iter()
+                .filter(|(index, _)| self.records.is_bookmarked(*index))
+                .map(|(_, lines)| lines.start)
+                .collect();
+            for (i, line) in lines.iter_mut().enumerate() {
+                let marker = if marked.contains(&i) {
+                    Span::styled("◆ ", self.theme.accent)
+                } else {
+                    Span::raw("  ")
+                };
+                line.spans.insert(0, marker);
+            }
+        }
+        if let Some((index, offset)) = anchor
+            && let Some((_, lines)) = entries.iter().find(|(i, _)| *i == index)
+        {
+            self.view.vertical_scroll = lines.start + offset.min(lines.len().saturating_sub(1));
+        }
+        self.view.entries = entries;
+
+        self.view.area_height = area.height.into();
 
-        if self.auto_scroll {
+        self.view.lines_len = lines.len();
+
+        // the area may have grown or the lines shrunk since the last render
+        self.view.vertical_scroll = self
+            .view
+            .vertical_scroll
+            .min(self.view.lines_len.saturating_sub(self.view.area_height));
+        self.view.vertical_scroll_state = self
+            .view
+            .vertical_scroll_state
+            .position(self.view.vertical_scroll);
+
+        if self.view.auto_scroll {
             self.scroll_to_bottom();
         }
+        if std::mem::take(&mut self.view.follow_cursor)
+            && let Some((_, lines)) = self
+                .view
+                .entries
+                .iter()
+                .find(|(index, _)| Some(*index) == self.view.cursor)
+        {
+            self.scroll_to_lines(lines.clone());
+        }
+        if let Some(line) = match_line {
+            self.scroll_to_lines(line..line + 1);
+        }
 
         // Render paragraph
+        // (only visible lines, Paragraph::scroll is limited to u16 lines)
 
-        Paragraph::new(lines)
-            .style(Style::default().fg(Color::White))
-            // .scroll((self.vertical_scroll as u16, 0))
+        let visible: Vec<_> = lines
+            .into_iter()
+            .skip(self.view.vertical_scroll)
+            .take(self.view.area_height)
+            .collect();
+        Paragraph::new(visible)
+            .style(self.theme.text)
             .render(area, buf);
 
         if self.show_scrollbar {
             // Render scroll
 
             let mut scroll_lines = 0;
-            if self.lines_len > area.height.into() {
-                scroll_lines = self.lines_len as i32 - self.area_height as i32 + 1;
+            if self.view.lines_len > area.height.into() {
+                scroll_lines = self.view.lines_len as i32 - self.view.area_height as i32 + 1;
             }
 
-            self.vertical_scroll_state = self
+            self.view.vertical_scroll_state = self
+                .view
                 .vertical_scroll_state
                 .content_length(scroll_lines as usize);
 
-            let scroll_style = if self.auto_scroll {
-                Style::default().fg(Color::DarkGray)
+            let scroll_style = if self.view.auto_scroll {
+                self.theme.muted
             } else {
-                Style::default().fg(Color::Yellow)
+                self.theme.accent
             };
 
             ratatui::widgets::Scrollbar::new(ratatui::widgets::ScrollbarOrientation::VerticalRight)
@@ -287,7 +1054,102 @@ impl ratatui::widgets::Widget for &mut Logs {
                 .track_style(scroll_style)
                 .thumb_symbol("▐")
                 .thumb_style(scroll_style)
-                .render(area, buf, &mut self.vertical_scroll_state);
+                .render(area, buf, &mut self.view.vertical_scroll_state);
+        }
+
+        if let Some(scrollbar_area) = horizontal_scrollbar_area {
+            let mut state = ratatui::widgets::ScrollbarState::new(self.view.max_horizontal_scroll)
+                .position(self.view.horizontal_scroll);
+            let scroll_style = self.theme.muted;
+            ratatui::widgets::Scrollbar::new(
+                ratatui::widgets::ScrollbarOrientation::HorizontalBottom,
+            )
+            .begin_symbol(Some("←"))
+            .begin_style(scroll_style)
+            .end_symbol(Some("→"))
+            .end_style(scroll_style)
+            .track_symbol(Some("."))
+            .track_style(scroll_style)
+            .thumb_symbol("▄")
+            .thumb_style(scroll_style)
+            .render(scrollbar_area, buf, &mut state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    const T0: Duration = Duration::from_secs(1_714_564_800);
+
+    /// Rows of the rendered logs, trimmed.
+    fn rows(logs: &mut Logs) -> Vec<String> {
+        let area = Rect::new(0, 0, 40, 8);
+        let mut buf = Buffer::empty(area);
+        ratatui::widgets::Widget::render(&mut *logs, area, &mut buf);
+        (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_owned()
+            })
+            .filter(|row| !row.is_empty())
+            .collect()
+    }
+
+    /// Records arriving `secs` after the start.
+    fn logs(records: &[(&str, u64)]) -> Logs {
+        let mut all = Records::new();
+        for (text, secs) in records {
+            let arrived_at = SystemTime::UNIX_EPOCH + T0 + Duration::from_secs(*secs);
+            all.add(Record::new(text.to_string()).with_arrived_at(arrived_at));
         }
+        let mut logs = Logs::new(all);
+        logs.set_show_scrollbar(false);
+        logs
+    }
+
+    #[test]
+    fn separates_records_further_apart_than_the_threshold() {
+        let mut logs = logs(&[("first", 0), ("second", 10), ("third", 135)]);
+        logs.set_gaps(Some(Duration::from_secs(30)), TimeSource::Arrival);
+        let rows = rows(&mut logs);
+        assert_eq!(rows.len(), 4, "{rows:?}");
+        assert!(rows[0].contains("first"));
+        assert!(rows[1].contains("second"));
+        assert!(rows[2].contains("─ 2m05s later ─"), "{rows:?}");
+        assert!(rows[3].contains("third"));
+
+        logs.toggle_gaps();
+        assert!(!rows_contain(&mut logs, "later"));
+    }
+
+    #[test]
+    fn measures_gaps_by_parsed_time_when_asked() {
+        // arrived together, written two minutes apart
+        let mut logs = logs(&[
+            ("2024-05-01T12:00:00Z started", 0),
+            ("2024-05-01T12:02:00Z stopped", 0),
+        ]);
+        logs.set_gaps(Some(Duration::from_secs(30)), TimeSource::Arrival);
+        assert!(!rows_contain(&mut logs, "later"));
+        logs.set_gaps(Some(Duration::from_secs(30)), TimeSource::Parsed);
+        assert!(rows_contain(&mut logs, "2m00s later"));
+    }
+
+    fn rows_contain(logs: &mut Logs, text: &str) -> bool {
+        rows(logs).iter().any(|row| row.contains(text))
+    }
+
+    #[test]
+    fn formats_gaps() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m05s");
+        assert_eq!(format_duration(Duration::from_secs(3720)), "1h02m");
     }
 }
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+use crate::internal::domain::records::Filter;
+use crate::internal::services::tui::logs::{Logs, View};
+use crate::internal::services::tui::search_input::SearchInput;
+use crate::internal::services::tui::theme::Theme;
+
+/// Percent of a split the focused pane grows or shrinks by.
+const RESIZE_STEP: u16 = 5;
+/// Smallest share of a split a pane keeps, in percent.
+const MIN_SHARE: u16 = 10;
+
+/// What makes a pane different from another one over the same records.
+#[derive(Default)]
+pub struct Pane {
+    view: View,
+    filter: Filter,
+    input: SearchInput,
+}
+
+impl Pane {
+    /// A pane showing what `filter` lets through, its search box holding the filter's text.
+    pub fn new(filter: Filter, theme: &Theme) -> Self {
+        let mut input = SearchInput::new();
+        input.set_theme(theme.clone());
+        input.paste(filter.key().as_ref());
+        Self {
+            filter,
+            input,
+            ..Default::default()
+        }
+    }
+
+    /// Trades places with the pane live in `logs` and `input`.
+    fn swap(&mut self, logs: &mut Logs, input: &mut SearchInput) {
+        logs.swap_view(&mut self.view);
+        logs.records_mut().swap_filter(&mut self.filter);
+        std::mem::swap(&mut self.input, input);
+    }
+}
+
+enum Node {
+    Pane(usize),
+    Split {
+        direction: Direction,
+        /// Percent of the area the first child takes.
+        share: u16,
+        first: Box<Node>,
+        second: Box<Node>,
+    },
+}
+
+impl Node {
+    fn ids(&self, ids: &mut Vec<usize>) {
+        match self {
+            Node::Pane(id) => ids.push(*id),
+            Node::Split { first, second, .. } => {
+                first.ids(ids);
+                second.ids(ids);
+            }
+        }
+    }
+
+    fn areas(&self, area: Rect, areas: &mut Vec<(usize, Rect)>) {
+        match self {
+            Node::Pane(id) => areas.push((*id, area)),
+            Node::Split {
+                direction,
+                share,
+                first,
+                second,
+            } => {
+                let [first_area, second_area] = Layout::new(
+                    *direction,
+                    [
+                        Constraint::Percentage(*share),
+                        Constraint::Percentage(100 - share),
+                    ],
+                )
+                .areas(area);
+                first.areas(first_area, areas);
+                second.areas(second_area, areas);
+            }
+        }
+    }
+
+    /// Puts `id` and `new` side by side in place of `id`.
+    fn split(&mut self, id: usize, new: usize, direction: Direction) -> bool {
+        match self {
+            Node::Pane(pane) if *pane == id => {
+                *self = Node::Split {
+                    direction,
+                    share: 50,
+                    first: Box::new(Node::Pane(id)),
+                    second: Box::new(Node::Pane(new)),
+                };
+                true
+            }
+            Node::Pane(_) => false,
+            Node::Split { first, second, .. } => {
+                first.split(id, new, direction) || second.split(id, new, direction)
+            }
+        }
+    }
+
+    /// Replaces the split holding `id` with the other side.
+    fn remove(&mut self, id: usize) -> bool {
+        let Node::Split { first, second, .. } = self else {
+            return false;
+        };
+        let rest = match (first.as_ref(), second.as_ref()) {
+            (Node::Pane(pane), _) if *pane == id => std::mem::replace(second, dummy()),
+            (_, Node::Pane(pane)) if *pane == id => std::mem::replace(first, dummy()),
+            _ => return first.remove(id) || second.remove(id),
+        };
+        *self = *rest;
+        true
+    }
+
+    /// Moves the border of the split holding `id` by `by` percent in its favor.
+    fn resize(&mut self, id: usize, by: i16) -> bool {
+        let Node::Split {
+            share,
+            first,
+            second,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        let by = match (first.as_ref(), second.as_ref()) {
+            (Node::Pane(pane), _) if *pane == id => by,
+            (_, Node::Pane(pane)) if *pane == id => -by,
+            _ => return first.resize(id, by) || second.resize(id, by),
+        };
+        *share = share
+            .saturating_add_signed(by)
+            .clamp(MIN_SHARE, 100 - MIN_SHARE);
+        true
+    }
+}
+
+fn dummy() -> Box<Node> {
+    Box::new(Node::Pane(usize::MAX))
+}
+
+/// Panes splitting the logs area, each with its own search, filters and scroll.
+///
+/// The focused pane lives in the app's `Logs` and `SearchInput`,
+/// the others wait here and get swapped in to be drawn.
+pub struct Panes {
+    root: Node,
+    /// Panes by id, the entry of the focused one is a leftover.
+    panes: BTreeMap<usize, Pane>,
+    focused: usize,
+    next_id: usize,
+}
+
+impl Default for Panes {
+    fn default() -> Self {
+        Self {
+            root: Node::Pane(0),
+            panes: BTreeMap::from([(0, Pane::default())]),
+            focused: 0,
+            next_id: 1,
+        }
+    }
+}
+
+impl Panes {
+    /// A single pane, `pane`, waiting to be swapped in.
+    pub fn new(pane: Pane) -> Self {
+        Self {
+            panes: BTreeMap::from([(0, pane)]),
+            ..Default::default()
+        }
+    }
+
+    /// There is more than one pane.
+    pub fn is_split(&self) -> bool {
+        self.panes.len() > 1
+    }
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    fn ids(&self) -> Vec<usize> {
+        let mut ids = vec![];
+        self.root.ids(&mut ids);
+        ids
+    }
+
+    /// Where every pane goes in `area`, with its id.
+    pub fn areas(&self, area: Rect) -> Vec<(usize, Rect)> {
+        let mut areas = vec![];
+        self.root.areas(area, &mut areas);
+        areas
+    }
+
+    /// Splits the focused pane in two and focuses `pane` in the new half.
+    pub fn split(
+        &mut self,
+        direction: Direction,
+        pane: Pane,
+        logs: &mut Logs,
+        input: &mut SearchInput,
+    ) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.root.split(self.focused, id, direction);
+        self.panes.insert(id, pane);
+        self.focus(id, logs, input);
+    }
+
+    /// Closes the focused pane unless it is the last one.
+    pub fn close(&mut self, logs: &mut Logs, input: &mut SearchInput) -> bool {
+        if self.panes.len() < 2 {
+            return false;
+        }
+        let closed = self.focused;
+        let ids = self.ids();
+        let position = ids.iter().position(|id| *id == closed).unwrap_or(0);
+        let next = if position + 1 < ids.len() {
+            ids[position + 1]
+        } else {
+            ids[position - 1]
+        };
+        self.focus(next, logs, input);
+        self.root.remove(closed);
+        self.panes.remove(&closed);
+        true
+    }
+
+    /// Focuses the next pane, or the previous one, in layout order.
+    pub fn cycle(&mut self, forward: bool, logs: &mut Logs, input: &mut SearchInput) {
+        let ids = self.ids();
+        let position = ids.iter().position(|id| *id == self.focused).unwrap_or(0);
+        let next = if forward {
+            (position + 1) % ids.len()
+        } else {
+            (position + ids.len() - 1) % ids.len()
+        };
+        self.focus(ids[next], logs, input);
+    }
+
+    pub fn focus(&mut self, id: usize, logs: &mut Logs, input: &mut SearchInput) {
+        if id == self.focused || !self.panes.contains_key(&id) {
+            return;
+        }
+        if let Some(pane) = self.panes.get_mut(&self.focused) {
+            pane.swap(logs, input);
+        }
+        if let Some(pane) = self.panes.get_mut(&id) {
+            pane.swap(logs, input);
+        }
+        self.focused = id;
+    }
+
+    /// Grows (or shrinks) the focused pane against its neighbor.
+    pub fn resize(&mut self, grow: bool) {
+        let by = if grow {
+            RESIZE_STEP as i16
+        } else {
+            -(RESIZE_STEP as i16)
+        };
+        self.root.resize(self.focused, by);
+    }
+
+    /// Trades the focused pane with the one live in `logs` and `input`,
+    /// putting it away when the panes go to the background and back when they return.
+    pub fn swap_focused(&mut self, logs: &mut Logs, input: &mut SearchInput) {
+        if let Some(pane) = self.panes.get_mut(&self.focused) {
+            pane.swap(logs, input);
+        }
+    }
+
+    /// Runs `f` with pane `id` live in `logs` and `input`.
+    pub fn with_pane<T>(
+        &mut self,
+        id: usize,
+        logs: &mut Logs,
+        input: &mut SearchInput,
+        f: impl FnOnce(&mut Logs, &mut SearchInput) -> T,
+    ) -> T {
+        if id == self.focused {
+            return f(logs, input);
+        }
+        let pane = self.panes.get_mut(&id).expect("pane exists");
+        pane.swap(logs, input);
+        let result = f(logs, input);
+        pane.swap(logs, input);
+        result
+    }
+
+    /// Drops filters of waiting panes that refer to records, when they are cleared.
+    pub fn forget_records(&mut self) {
+        for pane in self.panes.values_mut() {
+            pane.filter.forget_records();
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::cmp::Reverse;
+
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
+};
+
+use crate::internal::domain::records::Records;
+use crate::internal::domain::redact::Redactor;
+use crate::internal::services::tui::theme::Theme;
+
+/// Order of the pattern list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PatternSort {
+    /// Most frequent first
+    #[default]
+    Count,
+    /// Most recently seen first
+    Recent,
+}
+
+/// Overlay listing the patterns records are clustered into.
+#[derive(Default)]
+pub struct PatternList {
+    cursor: usize,
+    sort: PatternSort,
+}
+
+impl PatternList {
+    /// Pattern ids in the chosen order.
+    fn entries(&self, records: &Records) -> Vec<usize> {
+        let patterns = records.patterns();
+        let mut ids: Vec<usize> = (0..patterns.len()).collect();
+        // stable, so ties keep the order patterns appeared in
+        match self.sort {
+            PatternSort::Count => {
+                ids.sort_by_key(|id| Reverse(patterns.get(*id).map(|p| p.count())))
+            }
+            PatternSort::Recent => {
+                ids.sort_by_key(|id| Reverse(patterns.get(*id).map(|p| p.seen().1)))
+            }
+        }
+        ids
+    }
+
+    pub fn cursor_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+    pub fn cursor_down(&mut self, records: &Records) {
+        self.cursor = (self.cursor + 1).min(records.patterns().len().saturating_sub(1));
+    }
+    pub fn toggle_sort(&mut self) {
+        self.sort = match self.sort {
+            PatternSort::Count => PatternSort::Recent,
+            PatternSort::Recent => PatternSort::Count,
+        };
+        self.cursor = 0;
+    }
+    /// Id of the pattern under the cursor.
+    pub fn current(&self, records: &Records) -> Option<usize> {
+        self.entries(records).get(self.cursor).copied()
+    }
+}
+
+pub struct PatternsOverlay<'a> {
+    pub list: &'a PatternList,
+    pub records: &'a Records,
+    pub redactor: Option<&'a Redactor>,
+    pub theme: &'a Theme,
+}
+
+impl ratatui::widgets::Widget for PatternsOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let sort = match self.list.sort {
+            PatternSort::Count => "by count",
+            PatternSort::Recent => "by recency",
+        };
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(format!("Patterns {}", sort))
+            .padding(Padding::horizontal(1))
+            .style(self.theme.panel.patch(self.theme.accent));
+        let inner = block.inner(area);
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let [hints_area, list_area, sample_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(3),
+        ])
+        .areas(inner);
+
+        Paragraph::new(vec![
+            Line::from("enter -> show only, x -> hide, r -> reset, s -> sort, esc -> close"),
+            Line::from("   count  first    last"),
+        ])
+        .render(hints_area, buf);
+
+        let patterns = self.records.patterns();
+        let entries = self.list.entries(self.records);
+        let height = list_area.height as usize;
+        let scroll = (self.list.cursor + 1).saturating_sub(height);
+        let format = |time| chrono::DateTime::<chrono::Local>::from(time).format("%H:%M:%S");
+        let redact = |text: String| match self.redactor {
+            Some(redactor) => redactor.redact(&text).into_owned(),
+            None => text,
+        };
+        let lines: Vec<Line> = entries
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(height)
+            .filter_map(|(i, id)| {
+                let pattern = patterns.get(*id)?;
+                let mark = if self.records.shown_pattern() == Some(*id) {
+                    "✓"
+                } else if self.records.hidden_patterns().contains(id) {
+                    "✗"
+                } else {
+                    " "
+                };
+                let (first, last) = pattern.seen();
+                let selected = i == self.list.cursor;
+                let mut style = if selected {
+                    self.theme.selected
+                } else {
+                    Style::new()
+                };
+                if self.records.hidden_patterns().contains(id) {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+                Some(Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{} {:>6}  {} {}  ",
+                            mark,
+                            pattern.count(),
+                            format(first),
+                            format(last)
+                        ),
+                        style,
+                    ),
+                    Span::styled(
+                        redact(pattern.template()),
+                        if selected {
+                            style
+                        } else {
+                            style.patch(self.theme.text)
+                        },
+                    ),
+                ]))
+            })
+            .collect();
+        Paragraph::new(lines).render(list_area, buf);
+
+        if let Some(pattern) = self
+            .list
+            .current(self.records)
+            .and_then(|id| patterns.get(id))
+        {
+            Paragraph::new(vec![
+                Line::from("sample:"),
+                Line::styled(redact(pattern.sample().to_owned()), self.theme.text),
+            ])
+            .wrap(Wrap { trim: false })
+            .render(sample_area, buf);
+        } else {
+            Paragraph::new("no records yet").render(sample_area, buf);
+        }
+    }
+}
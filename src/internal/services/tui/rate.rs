@@ -0,0 +1,124 @@
+use std::time::{Duration, SystemTime};
+
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    text::Line,
+    widgets::{Block, Borders, Sparkline},
+};
+
+use crate::internal::domain::records::Records;
+use crate::internal::services::tui::theme::Theme;
+
+/// Height of the panel, borders included.
+pub const RATE_HEIGHT: u16 = 6;
+const LABEL_WIDTH: u16 = 7;
+
+/// What the panel under the logs shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateView {
+    #[default]
+    Off,
+    /// Records per second over the last minutes
+    Second,
+    /// Records per minute over the last hours
+    Minute,
+    /// The whole buffer by arrival time
+    Arrival,
+    /// The whole buffer by timestamps parsed from the records
+    Parsed,
+}
+
+impl RateView {
+    pub fn next(self) -> Self {
+        match self {
+            RateView::Off => RateView::Second,
+            RateView::Second => RateView::Minute,
+            RateView::Minute => RateView::Arrival,
+            RateView::Arrival => RateView::Parsed,
+            RateView::Parsed => RateView::Off,
+        }
+    }
+}
+
+/// Sparklines of all and matching records, live rate or histogram.
+pub struct RatePanel<'a> {
+    pub view: RateView,
+    pub records: &'a Records,
+    /// End of the live window, the replay clock while replaying.
+    pub now: SystemTime,
+    pub theme: &'a Theme,
+}
+
+impl ratatui::widgets::Widget for RatePanel<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let block = Block::new().borders(Borders::ALL);
+        let inner = block.inner(area);
+        let buckets = inner.width.saturating_sub(LABEL_WIDTH) as usize;
+
+        let (all, matching, title) = match self.view {
+            RateView::Off => return,
+            RateView::Second | RateView::Minute => {
+                let (bucket, unit) = if self.view == RateView::Second {
+                    (Duration::from_secs(1), "s")
+                } else {
+                    (Duration::from_secs(60), "min")
+                };
+                let (all, matching) = self.records.rate(bucket, buckets, self.now);
+                // the current bucket is still filling up, show the last complete one
+                let last = |counts: &[u64]| counts.len().checked_sub(2).map_or(0, |i| counts[i]);
+                let title = format!(
+                    "Rate: {}/{} all, {}/{} matching",
+                    last(&all),
+                    unit,
+                    last(&matching),
+                    unit
+                );
+                (all, matching, title)
+            }
+            RateView::Arrival | RateView::Parsed => {
+                let parsed = self.view == RateView::Parsed;
+                let (all, matching, span) = self.records.histogram(buckets, parsed);
+                let by = if parsed { "parsed time" } else { "arrival" };
+                let title = match span {
+                    Some(span) => {
+                        let long = span.end.duration_since(span.start).unwrap_or_default()
+                            > Duration::from_secs(24 * 60 * 60);
+                        let pattern = if long { "%m-%d %H:%M" } else { "%H:%M:%S" };
+                        let format =
+                            |time| chrono::DateTime::<chrono::Local>::from(time).format(pattern);
+                        format!(
+                            "Histogram by {}: {} – {}",
+                            by,
+                            format(span.start),
+                            format(span.end)
+                        )
+                    }
+                    None => format!("Histogram by {}", by),
+                };
+                (all, matching, title)
+            }
+        };
+
+        block.title(title).render(area, buf);
+        let rows = Layout::vertical([Constraint::Length(2), Constraint::Length(2)]).split(inner);
+        // one scale for both, so matching records compare to all of them
+        let max = all.iter().copied().max().unwrap_or(0).max(1);
+        for (row, label, data, style) in [
+            (rows[0], "all", &all, self.theme.muted),
+            (rows[1], "match", &matching, self.theme.accent),
+        ] {
+            let [label_area, spark_area] =
+                Layout::horizontal([Constraint::Length(LABEL_WIDTH), Constraint::Min(0)])
+                    .areas(row);
+            Line::styled(label, style).render(label_area, buf);
+            Sparkline::default()
+                .data(data)
+                .max(max)
+                .style(style)
+                .render(spark_area, buf);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::ops::Range;
+
+use ansi_to_tui::IntoText as _;
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+use regex::Regex;
+use serde_json::Value;
+
+use crate::internal::common::log_err;
+use crate::internal::domain::level::Level;
+use crate::internal::domain::record::{Format, LEVEL_KEYS, MESSAGE_KEYS, Record, TIME_KEYS};
+use crate::internal::services::tui::theme::Theme;
+
+/// How structured records are shown in the logs view.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    /// `ts level msg k=v…` on one line
+    #[default]
+    Compact,
+    /// Pretty printed JSON, other structured records one field per line
+    Pretty,
+    /// As received
+    Raw,
+}
+
+impl RenderMode {
+    pub fn next(self) -> Self {
+        match self {
+            RenderMode::Compact => RenderMode::Pretty,
+            RenderMode::Pretty => RenderMode::Raw,
+            RenderMode::Raw => RenderMode::Compact,
+        }
+    }
+}
+
+/// What part of a record is colored by its level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LevelColoring {
+    /// Only the level token
+    #[default]
+    Token,
+    /// The whole line
+    Line,
+    Off,
+}
+
+/// Style given to every match of a pattern, e.g. `timeout` in red.
+#[derive(Debug, Clone)]
+pub struct Highlight {
+    pub regex: Regex,
+    pub style: Style,
+}
+
+impl Highlight {
+    /// Char ranges of the matches in `text`.
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| {
+                let start = text[..m.start()].chars().count();
+                start..start + m.as_str().chars().count()
+            })
+            .collect()
+    }
+}
+
+pub fn render_record(
+    record: &Record,
+    mode: RenderMode,
+    coloring: LevelColoring,
+    theme: &Theme,
+) -> Text<'static> {
+    let mut text = match (record.format(), mode) {
+        (Format::Plain, _) | (_, RenderMode::Raw) => raw(record),
+        (_, RenderMode::Compact) => compact(record, coloring, theme),
+        (Format::Json, RenderMode::Pretty) => pretty(record, theme),
+        (_, RenderMode::Pretty) => field_per_line(record, coloring, theme),
+    };
+
+    let Some(level) = record.level() else {
+        return text;
+    };
+    match coloring {
+        LevelColoring::Token if record.format() == Format::Plain || mode == RenderMode::Raw => {
+            for line in text.lines.iter_mut() {
+                let content: String = line
+                    .spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect();
+                if let Some((_, range)) = Level::find(&content) {
+                    style_ranges(line, &[range], theme.level(level));
+                }
+            }
+        }
+        LevelColoring::Line => {
+            let color = theme.level(level);
+            for span in text.lines.iter_mut().flat_map(|line| line.spans.iter_mut()) {
+                // keep colors coming from the source
+                if span.style.fg.is_none() {
+                    span.style = span.style.patch(color);
+                }
+            }
+        }
+        _ => {}
+    }
+    text
+}
+
+fn raw(record: &Record) -> Text<'static> {
+    // can't use Text::from(record.to_string())
+    // because ANSI is not parsed properly
+    record
+        .to_string()
+        .into_text()
+        .unwrap_or(log_err("Error processing log").into())
+}
+
+fn compact(record: &Record, coloring: LevelColoring, theme: &Theme) -> Text<'static> {
+    let mut spans = vec![];
+    let time = record.find_field(&TIME_KEYS);
+    let level = record.find_field(&LEVEL_KEYS);
+    let message = record.find_field(&MESSAGE_KEYS);
+
+    if let Some((_, time)) = time {
+        spans.push(Span::styled(time.to_owned(), theme.muted));
+        spans.push(Span::raw(" "));
+    }
+    if let Some((key, level)) = level {
+        spans.push(Span::styled(
+            format!("{:<5}", level.to_uppercase()),
+            value_style(key, level, coloring, theme),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if let Some((_, message)) = message {
+        // default style, so the whole line coloring applies to the message
+        spans.push(Span::raw(message.to_owned()));
+        spans.push(Span::raw(" "));
+    }
+
+    let shown = [time, level, message].map(|field| field.map(|(key, _)| key));
+    for (key, value) in record.fields() {
+        if shown.contains(&Some(key.as_str())) {
+            continue;
+        }
+        spans.push(Span::styled(format!("{}=", key), theme.muted));
+        if value.contains(' ') || value.is_empty() {
+            spans.push(Span::styled(format!("{:?}", value), theme.value));
+        } else {
+            spans.push(Span::styled(value.clone(), theme.value));
+        }
+        spans.push(Span::raw(" "));
+    }
+    spans.pop();
+
+    Text::from(Line::from(spans))
+}
+
+fn pretty(record: &Record, theme: &Theme) -> Text<'static> {
+    let Ok(value) = serde_json::from_str::<Value>(record.as_ref().trim()) else {
+        return raw(record);
+    };
+    let mut lines = vec![];
+    pretty_value(&value, 0, None, false, theme, &mut lines);
+    Text::from(lines)
+}
+
+fn field_per_line(record: &Record, coloring: LevelColoring, theme: &Theme) -> Text<'static> {
+    let lines: Vec<_> = record
+        .fields()
+        .iter()
+        .map(|(key, value)| {
+            Line::from(vec![
+                Span::styled(format!("{}: ", key), theme.muted),
+                Span::styled(value.clone(), value_style(key, value, coloring, theme)),
+            ])
+        })
+        .collect();
+    Text::from(lines)
+}
+
+fn value_style(key: &str, value: &str, coloring: LevelColoring, theme: &Theme) -> Style {
+    match Level::from_field(value) {
+        Some(level) if LEVEL_KEYS.contains(&key) && coloring != LevelColoring::Off => {
+            theme.level(level)
+        }
+        _ => theme.value,
+    }
+}
+
+fn pretty_value(
+    value: &Value,
+    indent: usize,
+    key: Option<&str>,
+    comma: bool,
+    theme: &Theme,
+    lines: &mut Vec<Line<'static>>,
+) {
+    let padding = "  ".repeat(indent);
+    let comma = if comma { "," } else { "" };
+    let mut head = vec![Span::raw(padding.clone())];
+    if let Some(key) = key {
+        head.push(Span::styled(format!("{:?}", key), theme.muted));
+        head.push(Span::raw(": "));
+    }
+
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            head.push(Span::raw("{"));
+            lines.push(Line::from(head));
+            for (i, (key, value)) in map.iter().enumerate() {
+                pretty_value(
+                    value,
+                    indent + 1,
+                    Some(key),
+                    i + 1 < map.len(),
+                    theme,
+                    lines,
+                );
+            }
+            lines.push(Line::from(format!("{}}}{}", padding, comma)));
+        }
+        Value::Array(items) if !items.is_empty() => {
+            head.push(Span::raw("["));
+            lines.push(Line::from(head));
+            for (i, value) in items.iter().enumerate() {
+                pretty_value(value, indent + 1, None, i + 1 < items.len(), theme, lines);
+            }
+            lines.push(Line::from(format!("{}]{}", padding, comma)));
+        }
+        value => {
+            let style = match value {
+                Value::String(_) => theme.value,
+                Value::Number(_) => theme.number,
+                _ => theme.accent,
+            };
+            head.push(Span::styled(value.to_string(), style));
+            head.push(Span::raw(comma));
+            lines.push(Line::from(head));
+        }
+    }
+}
+
+/// Char ranges of case insensitive occurrences of `key` in `text`.
+pub fn find_matches(text: &str, key: &str) -> Vec<Range<usize>> {
+    let text: Vec<char> = text.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+    let mut ranges = vec![];
+    if key.is_empty() {
+        return ranges;
+    }
+
+    let mut start = 0;
+    while start + key.len() <= text.len() {
+        let found = text[start..start + key.len()]
+            .iter()
+            .zip(key.iter())
+            .all(|(t, k)| t.to_lowercase().eq(k.to_lowercase()));
+        if found {
+            ranges.push(start..start + key.len());
+            start += key.len();
+        } else {
+            start += 1;
+        }
+    }
+    ranges
+}
+
+/// Patches `style` over the chars of `line` within `ranges`, keeping other styles.
+pub fn style_ranges(line: &mut Line, ranges: &[Range<usize>], style: Style) {
+    if ranges.is_empty() {
+        return;
+    }
+
+    let mut spans = vec![];
+    let mut position = 0;
+    for span in line.spans.drain(..) {
+        let mut part = String::new();
+        let mut part_inside = false;
+        for c in span.content.chars() {
+            let inside = ranges.iter().any(|range| range.contains(&position));
+            if inside != part_inside && !part.is_empty() {
+                spans.push(styled_part(
+                    std::mem::take(&mut part),
+                    span.style,
+                    part_inside,
+                    style,
+                ));
+            }
+            part_inside = inside;
+            part.push(c);
+            position += 1;
+        }
+        if !part.is_empty() {
+            spans.push(styled_part(part, span.style, part_inside, style));
+        }
+    }
+    line.spans = spans;
+}
+
+fn styled_part(content: String, base: Style, inside: bool, style: Style) -> Span<'static> {
+    if inside {
+        Span::styled(content, base.patch(style))
+    } else {
+        Span::styled(content, base)
+    }
+}
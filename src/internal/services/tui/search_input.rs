@@ -1,36 +1,175 @@
-use crossterm::event::{KeyCode, KeyEvent};
-use ratatui::{
-    style::{Color, Style},
-    widgets::{Block, Paragraph},
-};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::{Block, Paragraph};
+use unicode_width::UnicodeWidthChar;
+
+use crate::internal::services::tui::theme::Theme;
 
 pub struct SearchInput {
     pub value: String,
     pub character_index: usize,
+    /// Active `field=value` filters, shown in the title.
+    value_filters: Vec<String>,
+    /// Columns of the text scrolled off to the left, when it is wider than the box.
+    scroll: usize,
+    theme: Theme,
 }
 impl SearchInput {
     pub fn new() -> Self {
         Self {
             value: String::from(""),
             character_index: 0,
+            value_filters: vec![],
+            scroll: 0,
+            theme: Theme::default(),
         }
     }
 
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
     pub fn clear(&mut self) {
         self.reset_cursor();
         self.value = String::from("");
     }
 
+    pub fn set_value_filters(&mut self, filters: Vec<String>) {
+        self.value_filters = filters;
+    }
+
+    /// Edits the text with readline keys.
     pub fn process_input(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers == KeyModifiers::CONTROL;
+        let alt = key.modifiers == KeyModifiers::ALT;
         match key.code {
-            KeyCode::Char(to_insert) => self.enter_char(to_insert),
+            KeyCode::Char('w') if ctrl => self.delete_to(self.blank_word_start()),
+            KeyCode::Char('u') if ctrl => self.delete_to(0),
+            KeyCode::Char('k') if ctrl => self.delete_to(self.value.chars().count()),
+            KeyCode::Char('b') if alt => self.character_index = self.word_start(),
+            KeyCode::Char('f') if alt => self.character_index = self.word_end(),
+            KeyCode::Char('d') if alt => self.delete_to(self.word_end()),
+            KeyCode::Backspace if alt => self.delete_to(self.word_start()),
+            KeyCode::Char(to_insert)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.enter_char(to_insert)
+            }
             KeyCode::Backspace => self.delete_char(),
+            KeyCode::Delete => self.delete_char_forward(),
             KeyCode::Left => self.move_cursor_left(),
             KeyCode::Right => self.move_cursor_right(),
+            KeyCode::Home => self.reset_cursor(),
+            KeyCode::End => self.character_index = self.value.chars().count(),
             KeyCode::Esc => self.clear(),
             _ => {}
         }
     }
+
+    /// Deletes the text before the cursor.
+    pub fn kill_to_start(&mut self) {
+        self.delete_to(0);
+    }
+
+    /// Inserts pasted text at the cursor, line breaks and tabs become spaces.
+    pub fn paste(&mut self, text: &str) {
+        for c in text.trim_end_matches(['\n', '\r']).chars() {
+            let c = if c.is_whitespace() { ' ' } else { c };
+            if !c.is_control() {
+                self.enter_char(c);
+            }
+        }
+    }
+
+    /// Puts the cursor at `column` of the box, e.g. where it was clicked.
+    pub fn set_cursor(&mut self, column: usize) {
+        let column = column + self.scroll;
+        let mut width = 0;
+        self.character_index = self
+            .value
+            .chars()
+            .position(|c| {
+                width += c.width().unwrap_or(0);
+                width > column
+            })
+            .unwrap_or(self.value.chars().count());
+    }
+
+    /// Column of the cursor in the box, as of the last render.
+    pub fn cursor_column(&self) -> u16 {
+        self.text_width(self.character_index)
+            .saturating_sub(self.scroll) as u16
+    }
+
+    /// Display width of the first `chars` chars.
+    fn text_width(&self, chars: usize) -> usize {
+        self.value
+            .chars()
+            .take(chars)
+            .map(|c| c.width().unwrap_or(0))
+            .sum()
+    }
+
+    /// Start of the word before the cursor, words being letters and digits.
+    fn word_start(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut index = self.character_index;
+        while index > 0 && !chars[index - 1].is_alphanumeric() {
+            index -= 1;
+        }
+        while index > 0 && chars[index - 1].is_alphanumeric() {
+            index -= 1;
+        }
+        index
+    }
+
+    /// End of the word after the cursor.
+    fn word_end(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut index = self.character_index;
+        while index < chars.len() && !chars[index].is_alphanumeric() {
+            index += 1;
+        }
+        while index < chars.len() && chars[index].is_alphanumeric() {
+            index += 1;
+        }
+        index
+    }
+
+    /// Start of the whitespace-separated word before the cursor, as `Ctrl+w` deletes.
+    fn blank_word_start(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut index = self.character_index;
+        while index > 0 && chars[index - 1].is_whitespace() {
+            index -= 1;
+        }
+        while index > 0 && !chars[index - 1].is_whitespace() {
+            index -= 1;
+        }
+        index
+    }
+
+    /// Deletes the chars between the cursor and `index`, leaving the cursor at the start.
+    fn delete_to(&mut self, index: usize) {
+        let (start, end) = if index < self.character_index {
+            (index, self.character_index)
+        } else {
+            (self.character_index, index)
+        };
+        self.value = self
+            .value
+            .chars()
+            .take(start)
+            .chain(self.value.chars().skip(end))
+            .collect();
+        self.character_index = start;
+    }
+
+    fn delete_char_forward(&mut self) {
+        self.delete_to(self.clamp_cursor(self.character_index + 1));
+    }
+
     fn move_cursor_left(&mut self) {
         let cursor_moved_left = self.character_index.saturating_sub(1);
         self.character_index = self.clamp_cursor(cursor_moved_left);
@@ -101,9 +240,100 @@ impl ratatui::widgets::Widget for &mut SearchInput {
     where
         Self: Sized,
     {
+        let mut title = String::from("Search [ctr+h for help]");
+        for filter in &self.value_filters {
+            title.push_str(" · ");
+            title.push_str(filter);
+        }
+
+        // scroll sideways to keep the cursor in the box
+        let width = (area.width as usize).saturating_sub(3);
+        let cursor = self.text_width(self.character_index);
+        if cursor < self.scroll {
+            self.scroll = cursor;
+        } else if cursor > self.scroll + width {
+            self.scroll = cursor - width;
+        }
+        self.scroll = self.scroll.min(
+            self.text_width(self.value.chars().count())
+                .saturating_sub(width),
+        );
+
         Paragraph::new(self.value.as_str())
-            .style(Style::default().fg(Color::Yellow))
-            .block(Block::bordered().title("Search [ctr+h for help]"))
+            .style(self.theme.accent)
+            .block(Block::bordered().title(title))
+            .scroll((0, self.scroll as u16))
             .render(area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(value: &str, cursor: usize) -> SearchInput {
+        let mut input = SearchInput::new();
+        input.paste(value);
+        input.character_index = cursor;
+        input
+    }
+
+    fn press(input: &mut SearchInput, code: KeyCode, modifiers: KeyModifiers) {
+        input.process_input(KeyEvent::new(code, modifiers));
+    }
+
+    #[test]
+    fn kills_to_the_start_and_the_end() {
+        let mut line = input("status=500 path=/api", 10);
+        press(&mut line, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(
+            (line.value.as_str(), line.character_index),
+            (" path=/api", 0)
+        );
+
+        let mut line = input("status=500 path=/api", 10);
+        press(&mut line, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!(
+            (line.value.as_str(), line.character_index),
+            ("status=500", 10)
+        );
+
+        let mut line = input("status=500 path=/api", 10);
+        line.kill_to_start();
+        assert_eq!(line.value, " path=/api");
+    }
+
+    #[test]
+    fn moves_and_deletes_by_words() {
+        let mut line = input("GET /api/users", 14);
+        press(&mut line, KeyCode::Char('b'), KeyModifiers::ALT);
+        assert_eq!(line.character_index, 9);
+        press(&mut line, KeyCode::Char('b'), KeyModifiers::ALT);
+        assert_eq!(line.character_index, 5);
+        press(&mut line, KeyCode::Char('f'), KeyModifiers::ALT);
+        assert_eq!(line.character_index, 8);
+        press(&mut line, KeyCode::Char('d'), KeyModifiers::ALT);
+        assert_eq!((line.value.as_str(), line.character_index), ("GET /api", 8));
+        press(&mut line, KeyCode::Backspace, KeyModifiers::ALT);
+        assert_eq!((line.value.as_str(), line.character_index), ("GET /", 5));
+    }
+
+    #[test]
+    fn deletes_blank_separated_words_in_multibyte_text() {
+        let mut line = input("ошибка 日本語/パス", 13);
+        press(&mut line, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!((line.value.as_str(), line.character_index), ("ошибка ", 7));
+        press(&mut line, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!((line.value.as_str(), line.character_index), ("", 0));
+    }
+
+    #[test]
+    fn pastes_at_the_cursor_without_line_breaks() {
+        let mut line = input("level= msg", 6);
+        line.paste("err\tor\n");
+        assert_eq!(
+            (line.value.as_str(), line.character_index),
+            ("level=err or msg", 12)
+        );
+    }
+}
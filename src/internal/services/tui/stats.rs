@@ -1,12 +1,28 @@
+use std::time::Duration;
+
 use ratatui::{
     prelude::*,
-    style::{Color, Style},
     widgets::{Block, Padding, Paragraph},
 };
 
+use crate::internal::domain::level::Level;
+use crate::internal::services::replay::ReplayStatus;
+use crate::internal::services::tui::logs::format_duration;
+use crate::internal::services::tui::theme::Theme;
+
 pub struct Stats {
     total: u32,
     current: u32,
+    replay: Option<ReplayStatus>,
+    level_counts: [usize; Level::ALL.len()],
+    min_level: Option<Level>,
+    /// Secrets are shown unmasked.
+    revealed: bool,
+    /// Sources silent for too long, with how long.
+    silent: Vec<(String, Duration)>,
+    /// Outcome of the last action, e.g. a copy.
+    message: Option<String>,
+    theme: Theme,
 }
 impl Default for Stats {
     fn default() -> Self {
@@ -19,12 +35,100 @@ impl Stats {
         Self {
             total: 0,
             current: 0,
+            replay: None,
+            level_counts: [0; Level::ALL.len()],
+            min_level: None,
+            revealed: false,
+            silent: vec![],
+            message: None,
+            theme: Theme::default(),
         }
     }
     pub fn set(&mut self, total: u32, current: u32) {
         self.total = total;
         self.current = current;
     }
+    pub fn set_replay(&mut self, replay: Option<ReplayStatus>) {
+        self.replay = replay;
+    }
+    pub fn set_levels(&mut self, counts: [usize; Level::ALL.len()], min_level: Option<Level>) {
+        self.level_counts = counts;
+        self.min_level = min_level;
+    }
+    pub fn set_revealed(&mut self, revealed: bool) {
+        self.revealed = revealed;
+    }
+    pub fn set_silent(&mut self, silent: Vec<(String, Duration)>) {
+        self.silent = silent;
+    }
+    pub fn set_message(&mut self, message: Option<String>) {
+        self.message = message;
+    }
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+    /// Width needed to render the stats, borders included.
+    pub fn width(&self) -> u16 {
+        (self.line().width() as u16 + 3).max(14)
+    }
+
+    fn line(&self) -> Line<'static> {
+        let mut spans = vec![];
+
+        if let Some(message) = &self.message {
+            spans.push(Span::styled(format!("{} │ ", message), self.theme.accent));
+        }
+
+        if let Some(min_level) = self.min_level {
+            spans.push(Span::styled(
+                format!("≥{} ", min_level),
+                self.theme.level(min_level),
+            ));
+        }
+        for (level, count) in Level::ALL.iter().zip(self.level_counts) {
+            if count > 0 {
+                let short = &level.as_str()[..1];
+                spans.push(Span::styled(
+                    format!("{}{} ", short, count),
+                    self.theme.level(*level),
+                ));
+            }
+        }
+        if !spans.is_empty() {
+            spans.push(Span::raw("│ "));
+        }
+
+        for (source, duration) in &self.silent {
+            spans.push(Span::styled(
+                format!("⚠ {} silent {} │ ", source, format_duration(*duration)),
+                self.theme.alert.bold(),
+            ));
+        }
+
+        if self.revealed {
+            spans.push(Span::styled("secrets shown │ ", self.theme.alert));
+        }
+
+        if let Some(replay) = &self.replay {
+            let state = if replay.finished {
+                "■"
+            } else if replay.paused {
+                "⏸"
+            } else {
+                "▶"
+            };
+            let clock = chrono::DateTime::<chrono::Local>::from(replay.clock);
+            spans.push(Span::raw(format!(
+                "{} {}x {} │ ",
+                state,
+                replay.speed,
+                clock.format("%H:%M:%S")
+            )));
+        }
+
+        spans.push(Span::raw(format!("{}/{}", self.current, self.total)));
+        Line::from(spans)
+    }
 }
 
 impl ratatui::widgets::Widget for &mut Stats {
@@ -35,10 +139,10 @@ impl ratatui::widgets::Widget for &mut Stats {
         let block = Block::bordered()
             .padding(Padding::new(0, 1, 0, 0))
             .title("Found")
-            .style(Color::DarkGray);
+            .style(self.theme.muted);
 
-        Paragraph::new(format!("{}/{}", self.current, self.total))
-            .style(Style::default().fg(Color::DarkGray))
+        Paragraph::new(self.line())
+            .style(self.theme.muted)
             .block(block)
             .alignment(Alignment::Right)
             .render(area, buf);
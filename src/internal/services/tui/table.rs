@@ -0,0 +1,269 @@
+use std::borrow::Cow;
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::internal::domain::record::{Format, LEVEL_KEYS, MESSAGE_KEYS, TIME_KEYS};
+use crate::internal::domain::records::Records;
+use crate::internal::domain::redact::Redactor;
+use crate::internal::services::tui::render::{LevelColoring, RenderMode, render_record};
+use crate::internal::services::tui::theme::Theme;
+
+/// Columns wider than that are truncated.
+const MAX_COLUMN_WIDTH: usize = 40;
+const SEPARATOR: &str = " │ ";
+
+/// Columnar view of structured records.
+pub struct Table {
+    /// Chosen columns in order, `None` shows every known field.
+    columns: Option<Vec<String>>,
+    /// Position in the column chooser.
+    cursor: usize,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Table::new()
+    }
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self {
+            columns: None,
+            cursor: 0,
+        }
+    }
+
+    /// Columns to show, well-known fields go first unless chosen otherwise.
+    pub fn columns(&self, keys: &[String]) -> Vec<String> {
+        if let Some(columns) = &self.columns {
+            return columns.clone();
+        }
+        let well_known: Vec<&str> = [&TIME_KEYS[..], &LEVEL_KEYS[..], &MESSAGE_KEYS[..]].concat();
+        let mut columns: Vec<String> = well_known
+            .iter()
+            .filter(|key| keys.iter().any(|k| k == *key))
+            .map(|key| key.to_string())
+            .collect();
+        columns.extend(
+            keys.iter()
+                .filter(|key| !well_known.contains(&key.as_str()))
+                .cloned(),
+        );
+        columns
+    }
+
+    /// Header and one line per record, not clipped to the area width.
+    pub fn lines(
+        &self,
+        records: &Records,
+        coloring: LevelColoring,
+        redactor: Option<&Redactor>,
+        theme: &Theme,
+    ) -> (Line<'static>, Vec<Line<'static>>) {
+        let columns = self.columns(records.keys());
+        let records: Vec<Cow<_>> = records
+            .iter()
+            .map(|record| match redactor {
+                Some(redactor) => redactor.redact_record(record),
+                None => Cow::Borrowed(record),
+            })
+            .collect();
+        let mut widths: Vec<usize> = columns.iter().map(|c| c.width()).collect();
+        for record in &records {
+            for (width, column) in widths.iter_mut().zip(&columns) {
+                let value = record.field(column).unwrap_or_default();
+                *width = (*width).max(cell(value).width()).min(MAX_COLUMN_WIDTH);
+            }
+        }
+
+        let header_style = theme
+            .text
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        let header = row(
+            columns
+                .iter()
+                .map(|column| Span::styled(column.clone(), header_style)),
+            &widths,
+            theme,
+        );
+
+        let rows = records
+            .iter()
+            .map(|record| {
+                if record.format() == Format::Plain {
+                    // unstructured records span the whole row
+                    render_record(record, RenderMode::Raw, coloring, theme)
+                        .lines
+                        .into_iter()
+                        .next()
+                        .unwrap_or_default()
+                } else {
+                    row(
+                        columns.iter().map(|column| {
+                            let value = cell(record.field(column).unwrap_or_default());
+                            match record.level() {
+                                Some(level) if LEVEL_KEYS.contains(&column.as_str()) => {
+                                    Span::styled(value, theme.level(level))
+                                }
+                                _ => Span::raw(value),
+                            }
+                        }),
+                        &widths,
+                        theme,
+                    )
+                }
+            })
+            .collect();
+
+        (header, rows)
+    }
+
+    /// Chosen columns first, then the rest of known fields.
+    fn chooser_entries(&self, keys: &[String]) -> Vec<(String, bool)> {
+        let columns = self.columns(keys);
+        let mut entries: Vec<_> = columns.iter().map(|c| (c.clone(), true)).collect();
+        entries.extend(
+            keys.iter()
+                .filter(|key| !columns.contains(key))
+                .map(|key| (key.clone(), false)),
+        );
+        entries
+    }
+
+    pub fn cursor_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+    pub fn cursor_down(&mut self, keys: &[String]) {
+        let last = self.chooser_entries(keys).len().saturating_sub(1);
+        self.cursor = (self.cursor + 1).min(last);
+    }
+
+    /// Shows or hides the column under the cursor.
+    pub fn toggle_column(&mut self, keys: &[String]) {
+        let entries = self.chooser_entries(keys);
+        let Some((key, shown)) = entries.get(self.cursor) else {
+            return;
+        };
+        let mut columns = self.columns(keys);
+        if *shown {
+            columns.retain(|c| c != key);
+        } else {
+            columns.push(key.clone());
+            self.cursor = columns.len() - 1;
+        }
+        self.columns = Some(columns);
+    }
+
+    /// Moves the column under the cursor left (`-1`) or right (`1`).
+    pub fn move_column(&mut self, keys: &[String], by: isize) {
+        let mut columns = self.columns(keys);
+        let target = self.cursor.checked_add_signed(by);
+        match target {
+            Some(target) if self.cursor < columns.len() && target < columns.len() => {
+                columns.swap(self.cursor, target);
+                self.cursor = target;
+                self.columns = Some(columns);
+            }
+            _ => {}
+        }
+    }
+
+    /// Goes back to showing every known field.
+    pub fn reset_columns(&mut self) {
+        self.columns = None;
+        self.cursor = 0;
+    }
+}
+
+/// Value of a cell on a single line.
+fn cell(value: &str) -> String {
+    value.replace(['\n', '\r', '\t'], " ")
+}
+
+fn row(
+    cells: impl Iterator<Item = Span<'static>>,
+    widths: &[usize],
+    theme: &Theme,
+) -> Line<'static> {
+    let mut spans = vec![];
+    for (i, (mut span, width)) in cells.zip(widths).enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(SEPARATOR, theme.muted));
+        }
+        span.content = truncate(&span.content, *width).into();
+        spans.push(span);
+    }
+    Line::from(spans)
+}
+
+/// Cuts or pads `value` to exactly `width` columns, marking cut values with `…`.
+fn truncate(value: &str, width: usize) -> String {
+    if value.width() <= width {
+        return format!("{}{}", value, " ".repeat(width - value.width()));
+    }
+    let mut result = String::new();
+    for c in value.chars() {
+        if result.width() + c.width().unwrap_or(0) + 1 > width {
+            break;
+        }
+        result.push(c);
+    }
+    result.push('…');
+    let padding = width.saturating_sub(result.width());
+    result + &" ".repeat(padding)
+}
+
+/// Overlay to choose and reorder table columns.
+pub struct ColumnChooser<'a> {
+    pub table: &'a Table,
+    pub keys: &'a [String],
+    pub theme: &'a Theme,
+}
+
+impl ratatui::widgets::Widget for ColumnChooser<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let mut lines = vec![
+            Line::from("space -> show/hide, shift+up/down -> move"),
+            Line::from("r -> reset, esc -> close"),
+            Line::from(""),
+        ];
+        for (i, (key, shown)) in self.table.chooser_entries(self.keys).iter().enumerate() {
+            let mark = if *shown { "[x] " } else { "[ ] " };
+            let style = if i == self.table.cursor {
+                self.theme.selected
+            } else {
+                Style::new()
+            };
+            lines.push(Line::styled(format!("{}{}", mark, key), style));
+        }
+        if self.keys.is_empty() {
+            lines.push(Line::from("no structured records yet"));
+        }
+
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title("Columns")
+            .padding(Padding::horizontal(1))
+            .style(self.theme.panel);
+
+        // keep the cursor (after 3 lines of hints) visible
+        let inner_height = area.height.saturating_sub(2) as usize;
+        let scroll = (self.table.cursor + 4).saturating_sub(inner_height);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .style(self.theme.accent)
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .render(area, buf);
+    }
+}
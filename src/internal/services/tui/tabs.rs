@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+
+use crate::internal::domain::records::{Filter, Records};
+use crate::internal::services::tui::logs::Logs;
+use crate::internal::services::tui::panes::{Pane, Panes};
+use crate::internal::services::tui::search_input::SearchInput;
+use crate::internal::services::tui::theme::Theme;
+
+/// A source, or a saved view over the records, with panes of its own.
+pub struct Tab {
+    name: String,
+    /// What the tab shows, its new panes start with it.
+    filter: Filter,
+    /// Panes of the tab while another one is active.
+    panes: Panes,
+    /// Records the filter let through since the tab was last active.
+    unread: usize,
+}
+
+impl Tab {
+    pub fn new(name: String, filter: Filter, theme: &Theme) -> Self {
+        Self {
+            name,
+            panes: Panes::new(Pane::new(filter.clone(), theme)),
+            filter,
+            unread: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+}
+
+/// Tabs over the records, the first one showing them all.
+///
+/// The panes of the active tab live in the app, like the focused pane does,
+/// the others wait here.
+pub struct Tabs {
+    tabs: Vec<Tab>,
+    active: usize,
+    /// Sources whose tab was closed, they do not get one again.
+    closed_sources: HashSet<String>,
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Self {
+            tabs: vec![Tab {
+                name: "all".to_owned(),
+                filter: Filter::default(),
+                panes: Panes::default(),
+                unread: 0,
+            }],
+            active: 0,
+            closed_sources: HashSet::new(),
+        }
+    }
+}
+
+impl Tabs {
+    /// There is more than one tab.
+    pub fn is_tabbed(&self) -> bool {
+        self.tabs.len() > 1
+    }
+    pub fn active(&self) -> usize {
+        self.active
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Tab> {
+        self.tabs.iter()
+    }
+
+    /// What the active tab shows.
+    pub fn filter(&self) -> &Filter {
+        &self.tabs[self.active].filter
+    }
+
+    /// Adds `tab` after the others and switches to it.
+    pub fn add(&mut self, tab: Tab, panes: &mut Panes, logs: &mut Logs, input: &mut SearchInput) {
+        self.tabs.push(tab);
+        self.switch(self.tabs.len() - 1, panes, logs, input);
+    }
+
+    /// Makes tab `index` active, `panes`, `logs` and `input` being the live state of the active one.
+    pub fn switch(
+        &mut self,
+        index: usize,
+        panes: &mut Panes,
+        logs: &mut Logs,
+        input: &mut SearchInput,
+    ) {
+        if index == self.active || index >= self.tabs.len() {
+            return;
+        }
+        panes.swap_focused(logs, input);
+        std::mem::swap(panes, &mut self.tabs[self.active].panes);
+        std::mem::swap(panes, &mut self.tabs[index].panes);
+        panes.swap_focused(logs, input);
+        self.active = index;
+        self.tabs[index].unread = 0;
+    }
+
+    /// Switches to the next tab, or the previous one.
+    pub fn cycle(
+        &mut self,
+        forward: bool,
+        panes: &mut Panes,
+        logs: &mut Logs,
+        input: &mut SearchInput,
+    ) {
+        let len = self.tabs.len();
+        let next = if forward {
+            (self.active + 1) % len
+        } else {
+            (self.active + len - 1) % len
+        };
+        self.switch(next, panes, logs, input);
+    }
+
+    /// Closes the active tab unless it is the last one.
+    pub fn close(&mut self, panes: &mut Panes, logs: &mut Logs, input: &mut SearchInput) -> bool {
+        if self.tabs.len() < 2 {
+            return false;
+        }
+        let closed = self.active;
+        let next = if closed + 1 < self.tabs.len() {
+            closed + 1
+        } else {
+            closed - 1
+        };
+        self.switch(next, panes, logs, input);
+        let tab = self.tabs.remove(closed);
+        if let Some(source) = tab.filter.source_name() {
+            self.closed_sources.insert(source.to_owned());
+        }
+        if self.active > closed {
+            self.active -= 1;
+        }
+        true
+    }
+
+    pub fn rename(&mut self, name: String) {
+        self.tabs[self.active].name = name;
+    }
+
+    /// Counts the record at `index` as unread in the background tabs it shows in,
+    /// and opens a tab for its source once there are several.
+    pub fn record_added(&mut self, records: &Records, index: usize, theme: &Theme) {
+        if records.last_arrivals().len() > 1 {
+            let mut sources: Vec<_> = records
+                .last_arrivals()
+                .keys()
+                .filter(|source| {
+                    !self.closed_sources.contains(*source)
+                        && !self
+                            .tabs
+                            .iter()
+                            .any(|tab| tab.filter.source_name() == Some(source.as_str()))
+                })
+                .cloned()
+                .collect();
+            sources.sort();
+            for source in sources {
+                let mut tab = Tab::new(source.clone(), Filter::source(&source), theme);
+                // records came before the tab did
+                tab.unread = (0..records.len())
+                    .filter(|i| *i != index && records.matches_filter(&tab.filter, *i))
+                    .count();
+                self.tabs.push(tab);
+            }
+        }
+        for (i, tab) in self.tabs.iter_mut().enumerate() {
+            if i != self.active && records.matches_filter(&tab.filter, index) {
+                tab.unread += 1;
+            }
+        }
+    }
+
+    /// Drops what refers to records, when they are cleared.
+    pub fn forget_records(&mut self) {
+        for tab in self.tabs.iter_mut() {
+            tab.filter.forget_records();
+            tab.panes.forget_records();
+            tab.unread = 0;
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+};
+
+use crate::internal::domain::level::Level;
+
+/// Styles of everything `lf` draws itself, records keep their own ANSI colors
+/// unless the theme has no colors at all.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Logs and list entries.
+    pub text: Style,
+    /// Keys of fields, timestamps, separators, scrollbars.
+    pub muted: Style,
+    /// Titles, borders and headings.
+    pub accent: Style,
+    /// Values of fields.
+    pub value: Style,
+    /// Numbers in pretty printed JSON.
+    pub number: Style,
+    /// Search matches.
+    pub found: Style,
+    /// Entry under the cursor in lists.
+    pub selected: Style,
+    /// Background of overlays.
+    pub panel: Style,
+    /// Records in the visual selection.
+    pub selection: Style,
+    /// Record under the line cursor.
+    pub cursor: Style,
+    /// Silent sources and revealed secrets.
+    pub alert: Style,
+    /// Count of collapsed duplicates.
+    pub badge: Style,
+    pub trace: Style,
+    pub debug: Style,
+    pub info: Style,
+    pub warn: Style,
+    pub error: Style,
+    pub fatal: Style,
+    /// `false` drops every color on screen, ANSI colors of records too.
+    pub colors: bool,
+}
+
+impl Theme {
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            text: Style::new().fg(Color::White),
+            muted: Style::new().fg(Color::DarkGray),
+            accent: Style::new().fg(Color::Yellow),
+            value: Style::new().fg(Color::Cyan),
+            number: Style::new().fg(Color::Magenta),
+            found: Style::new().bg(Color::Yellow).fg(Color::Black),
+            selected: Style::new().bg(Color::Yellow).fg(Color::Black),
+            panel: Style::new().bg(Color::Black),
+            selection: Style::new().bg(Color::Blue),
+            cursor: Style::new().bg(Color::DarkGray),
+            alert: Style::new().fg(Color::Red),
+            badge: Style::new().fg(Color::Black).bg(Color::Magenta),
+            trace: Style::new().fg(Color::DarkGray),
+            debug: Style::new().fg(Color::Cyan),
+            info: Style::new().fg(Color::Green),
+            warn: Style::new().fg(Color::Yellow),
+            error: Style::new().fg(Color::Red),
+            fatal: Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+            colors: true,
+        }
+    }
+
+    /// For terminals with a light background, yellow and cyan are hard to read there.
+    pub fn light() -> Self {
+        Self {
+            text: Style::new(),
+            muted: Style::new().fg(Color::DarkGray),
+            accent: Style::new().fg(Color::Blue),
+            value: Style::new().fg(Color::Magenta),
+            number: Style::new().fg(Color::Red),
+            found: Style::new().bg(Color::LightYellow).fg(Color::Black),
+            selected: Style::new().bg(Color::Blue).fg(Color::White),
+            panel: Style::new(),
+            selection: Style::new().bg(Color::LightBlue),
+            cursor: Style::new().bg(Color::Gray),
+            alert: Style::new().fg(Color::Red),
+            badge: Style::new().fg(Color::White).bg(Color::Magenta),
+            trace: Style::new().fg(Color::DarkGray),
+            debug: Style::new().fg(Color::Blue),
+            info: Style::new().fg(Color::Green),
+            warn: Style::new().fg(Color::Indexed(130)),
+            error: Style::new().fg(Color::Red),
+            fatal: Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+            colors: true,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            text: Style::new().fg(Color::White),
+            muted: Style::new().fg(Color::Gray),
+            accent: Style::new()
+                .fg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            value: Style::new().fg(Color::LightCyan),
+            number: Style::new().fg(Color::LightMagenta),
+            found: Style::new()
+                .bg(Color::LightYellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            selected: Style::new().bg(Color::White).fg(Color::Black),
+            panel: Style::new().bg(Color::Black),
+            selection: Style::new().bg(Color::Blue).add_modifier(Modifier::BOLD),
+            cursor: Style::new().bg(Color::White).fg(Color::Black),
+            alert: Style::new()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+            badge: Style::new().fg(Color::Black).bg(Color::LightMagenta),
+            trace: Style::new().fg(Color::Gray),
+            debug: Style::new().fg(Color::LightCyan),
+            info: Style::new().fg(Color::LightGreen),
+            warn: Style::new().fg(Color::LightYellow),
+            error: Style::new()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+            fatal: Style::new()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            colors: true,
+        }
+    }
+
+    /// Bold, dim and reversed text only, for `NO_COLOR` and monochrome terminals.
+    pub fn monochrome() -> Self {
+        let bold = Style::new().add_modifier(Modifier::BOLD);
+        let reversed = Style::new().add_modifier(Modifier::REVERSED);
+        Self {
+            text: Style::new(),
+            muted: Style::new().add_modifier(Modifier::DIM),
+            accent: bold,
+            value: Style::new(),
+            number: Style::new(),
+            found: reversed,
+            selected: reversed,
+            panel: Style::new(),
+            selection: reversed,
+            cursor: Style::new().add_modifier(Modifier::UNDERLINED),
+            alert: bold,
+            badge: reversed,
+            trace: Style::new().add_modifier(Modifier::DIM),
+            debug: Style::new(),
+            info: Style::new(),
+            warn: bold,
+            error: bold,
+            fatal: Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            colors: false,
+        }
+    }
+
+    /// `NO_COLOR` is set (see <https://no-color.org>) or the terminal is `dumb`.
+    pub fn colorless_terminal() -> bool {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        let dumb = std::env::var_os("TERM").is_some_and(|term| term == "dumb");
+        no_color || dumb
+    }
+
+    pub fn level(&self, level: Level) -> Style {
+        match level {
+            Level::Trace => self.trace,
+            Level::Debug => self.debug,
+            Level::Info => self.info,
+            Level::Warn => self.warn,
+            Level::Error => self.error,
+            Level::Fatal => self.fatal,
+        }
+    }
+
+    /// The style named `name` in the config, `None` if there is no such style.
+    pub fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        let style = match name {
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "accent" => &mut self.accent,
+            "value" => &mut self.value,
+            "number" => &mut self.number,
+            "found" => &mut self.found,
+            "selected" => &mut self.selected,
+            "panel" => &mut self.panel,
+            "selection" => &mut self.selection,
+            "cursor" => &mut self.cursor,
+            "alert" => &mut self.alert,
+            "badge" => &mut self.badge,
+            "trace" => &mut self.trace,
+            "debug" => &mut self.debug,
+            "info" => &mut self.info,
+            "warn" => &mut self.warn,
+            "error" => &mut self.error,
+            "fatal" => &mut self.fatal,
+            _ => return None,
+        };
+        Some(style)
+    }
+
+    /// Drops the colors of the drawn frame when the theme has none.
+    pub fn finish(&self, buf: &mut Buffer) {
+        if self.colors {
+            return;
+        }
+        for cell in buf.content.iter_mut() {
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
@@ -1,13 +1,23 @@
 pub mod internal;
+use clap::{Parser, Subcommand};
 use internal::domain::event::Event;
 use internal::services::tui::{App, key_inputs::handle_key_inputs};
 use std::env;
 use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::time::Duration;
 use std::{sync::mpsc, thread::spawn};
 
 use crate::internal::domain::record::Record;
+use crate::internal::services::capture::{Capture, CaptureError, read_capture};
+use crate::internal::services::clipboard::Clipboard;
+use crate::internal::services::config::{Config, ConfigError};
+use crate::internal::services::generator::{Generator, GeneratorConfig};
+use crate::internal::services::replay::Replay;
 use crate::internal::services::tui::TuiError;
+use crate::internal::services::tui::render::LevelColoring;
+use crate::internal::services::tui::theme::Theme;
 
 #[derive(thiserror::Error, Debug)]
 pub enum AppError {
@@ -17,6 +27,67 @@ pub enum AppError {
     IO(#[from] std::io::Error),
     #[error(transparent)]
     Send(#[from] std::sync::mpsc::SendError<Event>),
+    #[error(transparent)]
+    Capture(#[from] CaptureError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+/// Program to filter logs stream.
+///
+/// Reads log entries from stdin, or runs COMMAND and reads its stdout/stderr.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Config file [default: $XDG_CONFIG_HOME/lf/config.toml]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Write every record with its arrival time to FILE (see `lf replay`)
+    #[arg(long, value_name = "FILE")]
+    capture: Option<PathBuf>,
+    /// Start without mouse capture, leaving text selection to the terminal (`Alt+x` toggles it)
+    #[arg(long)]
+    no_mouse: bool,
+    /// What part of a record is colored by its level
+    #[arg(long, value_enum, default_value_t = LevelColoring::Token)]
+    level_color: LevelColoring,
+    /// Color theme: dark, light, high-contrast, monochrome or one from the config
+    /// [default: `theme.name` from the config, monochrome with NO_COLOR]
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Generate a synthetic log stream (for demos, load and rendering tests)
+    Generate {
+        /// Records per second (bursts go 20 times faster)
+        #[arg(long, default_value_t = 10.0)]
+        rate: f64,
+        /// Same seed produces the same stream
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Stop after COUNT records
+        #[arg(long)]
+        count: Option<usize>,
+        /// Keep a steady rate, without bursts and quiet periods
+        #[arg(long)]
+        no_bursts: bool,
+    },
+    /// Replay a file written with `--capture`, keeping the original timing
+    Replay {
+        file: PathBuf,
+        /// Playback speed multiplier
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+        /// Start paused, emitting records one by one with `Alt+n`
+        #[arg(long)]
+        step: bool,
+    },
+    #[command(external_subcommand)]
+    Run(Vec<String>),
 }
 
 // TODO:
@@ -26,124 +97,189 @@ fn main() -> Result<(), AppError> {
 
     let orig_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
+        let _ = crossterm::execute!(
+            std::io::stdout(),
+            crossterm::event::DisableMouseCapture,
+            crossterm::event::DisableBracketedPaste,
+            crossterm::event::DisableFocusChange
+        );
         orig_hook(panic_info);
         std::process::exit(1);
     }));
 
+    let cli = Cli::parse();
+    let config = Config::load(cli.config.as_deref())?;
+    let rules = config.rules()?;
+    let captures = config.captures()?;
+    let redactor = config.redactor()?;
+    let highlights = config.highlights()?;
+    let keymap = config.keymap()?;
+    let gap_threshold = config.gaps.threshold()?;
+    let silence_alert = config.gaps.silence_alert()?;
+    // NO_COLOR and dumb terminals get no colors, unless a theme is asked for
+    let theme = match cli.theme.as_deref() {
+        None if Theme::colorless_terminal() => Theme::monochrome(),
+        name => config.theme(name)?,
+    };
+
     // Create channels
 
     let (rr_tx, rr_rx) = mpsc::channel::<Event>();
 
+    // Records go through the capture (if any) before reaching the app
+    let records_tx = match cli.capture {
+        Some(path) => {
+            let mut capture = Capture::create(&path)?;
+            let (capture_tx, capture_rx) = mpsc::channel::<Event>();
+            let rr_tx_capture = rr_tx.clone();
+            let capture_redactor = redactor.clone();
+            spawn(move || {
+                for event in capture_rx {
+                    if let Event::StdIn(record) = &event {
+                        capture
+                            .write(&capture_redactor.redact_record(record))
+                            .expect("couldn't write capture");
+                    }
+                    rr_tx_capture
+                        .send(event)
+                        .expect("couldn't send captured event");
+                }
+            });
+            capture_tx
+        }
+        None => rr_tx.clone(),
+    };
+
     let mut child_process: Option<Child> = None;
+    let mut replay_handle = None;
 
-    if let Ok(mock) = env::var("MOCK")
-        && mock
-            .to_lowercase()
-            .parse()
-            .expect("should contain true/false, case is ignored")
-    {
-        let rr_tx_stdin = rr_tx.clone();
-        spawn(move || {
-            loop {
-                || -> Result<(), AppError> {
-                    for i in 1..37 {
-                        let r = Record::new(format!(r#"{:?} - key=01556cbc-b520-48e5-b5c8-fa25b4211cc3, value="ID":"01556cbc-b520-48e5-b5c8-fa25b4211cc3","PrivateKey":"0x0x93ed6fbc2f7d1922fd8a3932a58ae713465d4dfb0db83fe74b21d523d015cb01","TelegramID":"279058397""#, i).to_string());
-                        rr_tx_stdin.send(Event::StdIn(r))?;
-                    }
-                    // thread::sleep(std::time::Duration::from_secs(10000));
-                    for i in 36..360 {
-                        let r = Record::new(format!(r#"{:?} - key=01556cbc-b520-48e5-b5c8-fa25b4211cc3, value="ID":"01556cbc-b520-48e5-b5c8-fa25b4211cc3","PrivateKey":"0x0x93ed6fbc2f7d1922fd8a3932a58ae713465d4dfb0db83fe74b21d523d015cb01","TelegramID":"279058397""#, i).to_string());
-                        rr_tx_stdin.send(Event::StdIn(r))?;
-                        std::thread::sleep(std::time::Duration::from_secs(1));
-                    }
+    match cli.command {
+        None => {
+            // Reading log entries from stdin
 
-                    Ok(())
-                }()
-                .expect("reading log from stdin");
-            }
-        });
-    } else {
-        let args: Vec<_> = std::env::args().collect(); // get all arguments passed to app
-
-        match args.len() {
-            1 => {
-                // Reading log entries from stdin
-
-                let rr_tx_stdin = rr_tx.clone();
-                spawn(move || {
-                    loop {
-                        || -> Result<(), AppError> {
-                            if atty::is(atty::Stream::Stdin) {
-                                return Ok(());
-                            }
-                            let mut line = String::new();
-                            std::io::stdin().read_line(&mut line)?;
-
-                            rr_tx_stdin.send(Event::StdIn(line.into()))?;
-
-                            Ok(())
-                        }()
-                        .expect("reading log from stdin");
-                    }
-                });
-            }
-            _ => {
-                // Run child process
-
-                || -> Result<(), AppError> {
-                    let system_command = args[1].clone();
-                    let arguments = args[2..].iter();
-                    let current_dir = env::current_dir()?;
-                    let mut child = Command::new(system_command)
-                        .args(arguments)
-                        .current_dir(current_dir)
-                        .stdout(Stdio::piped())
-                        .stderr(Stdio::piped())
-                        .spawn()
-                        .expect("should be able to execute `echo`");
-
-                    let stdout = child.stdout.take();
-                    if let Some(stdout) = stdout {
-                        let rr_tx_stdout = rr_tx.clone();
-                        spawn(move || {
-                            let stdout_reader = BufReader::new(stdout);
-                            let stdout_lines = stdout_reader.lines();
-
-                            for line in stdout_lines {
-                                rr_tx_stdout
-                                    .send(Event::StdIn(line.unwrap().into()))
-                                    .expect("couldn't send stdout event");
-                            }
-                        });
-                    }
+            let rr_tx_stdin = records_tx.clone();
+            spawn(move || {
+                loop {
+                    || -> Result<(), AppError> {
+                        if atty::is(atty::Stream::Stdin) {
+                            return Ok(());
+                        }
+                        let mut line = String::new();
+                        std::io::stdin().read_line(&mut line)?;
 
-                    let stderr = child.stderr.take();
-                    if let Some(stderr) = stderr {
-                        let rr_tx_stderr = rr_tx.clone();
-                        spawn(move || {
-                            let stderr_reader = BufReader::new(stderr);
-                            let stderr_lines = stderr_reader.lines();
-
-                            for line in stderr_lines {
-                                rr_tx_stderr
-                                    .send(Event::StdIn(line.unwrap().into()))
-                                    .expect("couldn't send stderr event");
-                            }
-                        });
-                    }
+                        let record = Record::new(line).with_source("stdin");
+                        rr_tx_stdin.send(Event::StdIn(record))?;
+
+                        Ok(())
+                    }()
+                    .expect("reading log from stdin");
+                }
+            });
+        }
+        Some(CliCommand::Generate {
+            rate,
+            seed,
+            count,
+            no_bursts,
+        }) => {
+            // Generate synthetic log entries
+
+            let generator = Generator::new(GeneratorConfig {
+                rate,
+                seed,
+                count,
+                bursts: !no_bursts,
+            });
+            let rr_tx_generator = records_tx.clone();
+            spawn(move || {
+                generator
+                    .run(rr_tx_generator)
+                    .expect("couldn't send generated event");
+            });
+        }
+        Some(CliCommand::Replay { file, speed, step }) => {
+            // Replay captured log entries
+
+            let replay = Replay::new(read_capture(&file)?, speed, step);
+            replay_handle = Some(replay.handle());
+            let rr_tx_replay = records_tx.clone();
+            spawn(move || {
+                replay
+                    .run(rr_tx_replay)
+                    .expect("couldn't send replay event");
+            });
+        }
+        Some(CliCommand::Run(args)) => {
+            // Run child process
+
+            || -> Result<(), AppError> {
+                let system_command = args[0].clone();
+                let arguments = args[1..].iter();
+                let source = Path::new(&system_command)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| system_command.clone());
+                let current_dir = env::current_dir()?;
+                let mut child = Command::new(system_command)
+                    .args(arguments)
+                    .current_dir(current_dir)
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .expect("should be able to execute `echo`");
+
+                let stdout = child.stdout.take();
+                if let Some(stdout) = stdout {
+                    let rr_tx_stdout = records_tx.clone();
+                    let source = source.clone();
+                    spawn(move || {
+                        let stdout_reader = BufReader::new(stdout);
+                        let stdout_lines = stdout_reader.lines();
+
+                        for line in stdout_lines {
+                            rr_tx_stdout
+                                .send(Event::StdIn(
+                                    Record::new(line.unwrap()).with_source(&source),
+                                ))
+                                .expect("couldn't send stdout event");
+                        }
+                    });
+                }
 
-                    child_process = Some(child);
+                let stderr = child.stderr.take();
+                if let Some(stderr) = stderr {
+                    let rr_tx_stderr = records_tx.clone();
+                    let source = source.clone();
+                    spawn(move || {
+                        let stderr_reader = BufReader::new(stderr);
+                        let stderr_lines = stderr_reader.lines();
 
-                    Ok(())
-                }()
-                .expect("running subprocess");
-            }
+                        for line in stderr_lines {
+                            rr_tx_stderr
+                                .send(Event::StdIn(
+                                    Record::new(line.unwrap()).with_source(&source),
+                                ))
+                                .expect("couldn't send stderr event");
+                        }
+                    });
+                }
+
+                child_process = Some(child);
+
+                Ok(())
+            }()
+            .expect("running subprocess");
         }
     }
 
     // Init terminal
 
     let mut terminal = ratatui::init();
+    crossterm::execute!(
+        std::io::stdout(),
+        crossterm::event::EnableBracketedPaste,
+        crossterm::event::EnableFocusChange
+    )?;
 
     // Reading key inputs
 
@@ -152,13 +288,44 @@ fn main() -> Result<(), AppError> {
         handle_key_inputs(rr_tx_keys);
     });
 
+    // Ticking, so live views move while no records come
+
+    let rr_tx_ticks = rr_tx.clone();
+    spawn(move || {
+        while rr_tx_ticks.send(Event::Tick).is_ok() {
+            std::thread::sleep(Duration::from_secs(1));
+        }
+    });
+
     // Run
 
     let mut tui = App::new(rr_rx);
+    tui.set_mouse_capture(!cli.no_mouse)?;
+    tui.set_level_coloring(cli.level_color);
+    tui.set_rules(rules);
+    tui.set_captures(captures);
+    tui.set_redactor(redactor);
+    tui.set_highlights(highlights);
+    tui.set_keymap(keymap);
+    tui.set_theme(theme);
+    tui.set_clipboard(Clipboard::new(
+        config.clipboard.osc52,
+        config.clipboard.file,
+    ));
+    tui.set_gaps(gap_threshold, config.gaps.time, silence_alert);
+    if let Some(replay) = replay_handle {
+        tui.set_replay(replay);
+    }
     let result = tui.run(&mut terminal);
 
     // Shutdown
 
+    crossterm::execute!(
+        std::io::stdout(),
+        crossterm::event::DisableMouseCapture,
+        crossterm::event::DisableBracketedPaste,
+        crossterm::event::DisableFocusChange
+    )?;
     ratatui::restore();
     if let Some(mut cp) = child_process {
         cp.kill()?;